@@ -5,17 +5,24 @@ use std::io::Write;
 use std::path::Path;
 
 fn main() {
+    let mut extensions = vec![
+        "GL_ARB_texture_filter_anisotropic",
+        "GL_NV_shader_thread_group",
+        "GL_EXT_texture_compression_s3tc",
+        "GL_EXT_texture_sRGB",
+        "GL_ARB_indirect_parameters",
+    ];
+
+    if env::var_os("CARGO_FEATURE_SPIRV").is_some() {
+        extensions.push("GL_ARB_gl_spirv");
+    }
+
     let gl_registry = Registry::new(
         Api::Gl,
         (4, 5),
         Profile::Core,
         Fallbacks::All,
-        [
-            "GL_ARB_texture_filter_anisotropic",
-            "GL_NV_shader_thread_group",
-            "GL_EXT_texture_compression_s3tc",
-            "GL_EXT_texture_sRGB",
-        ],
+        extensions,
     );
 
     let out_dir = env::var("OUT_DIR").unwrap();
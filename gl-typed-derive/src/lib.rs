@@ -0,0 +1,278 @@
+//! Derive macros for `gl-typed`. Enable the `derive` feature of `gl-typed` to
+//! use them through `gl_typed::VertexLayout` and `gl_typed::Std140`.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Lit, Meta, NestedMeta};
+
+/// Implements `VertexLayout` for a struct with named fields.
+///
+/// Every field becomes an attribute whose format is taken from its type's
+/// `VertexAttributeType` implementation. Use `#[vertex(format = "...")]` with
+/// an expression that converts into an `AttributeFormat` to override it, and
+/// `#[vertex(skip)]` to leave a field out, for example padding.
+#[proc_macro_derive(VertexLayout, attributes(vertex))]
+pub fn derive_vertex_layout(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match vertex_layout(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Implements `BlockMember`, `BlockStruct` and `AsStd140` for a struct with
+/// named fields.
+///
+/// Every field must implement `AsStd140`. A `#[repr(C)]` struct with the
+/// same name suffixed by `Std140` is generated next to it. It holds the
+/// std140 representation of every field and explicit padding so that it can
+/// be copied into a uniform buffer as is.
+#[proc_macro_derive(Std140)]
+pub fn derive_std140(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match std140(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+enum FieldFormat {
+    Inferred,
+    Explicit(syn::Expr),
+    Skip,
+}
+
+fn field_format(field: &syn::Field) -> Result<FieldFormat, Error> {
+    let mut format = FieldFormat::Inferred;
+    for attr in field.attrs.iter().filter(|a| a.path.is_ident("vertex")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new(meta.span(), "expected #[vertex(...)]")),
+        };
+        for nested in list.nested.iter() {
+            match nested {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip") => {
+                    format = FieldFormat::Skip;
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("format") => {
+                    match &nv.lit {
+                        Lit::Str(s) => format = FieldFormat::Explicit(s.parse()?),
+                        lit => {
+                            return Err(Error::new(
+                                lit.span(),
+                                "expected a string containing an expression",
+                            ))
+                        }
+                    }
+                }
+                nested => {
+                    return Err(Error::new(
+                        nested.span(),
+                        "expected `skip` or `format = \"...\"`",
+                    ))
+                }
+            }
+        }
+    }
+    Ok(format)
+}
+
+fn vertex_layout(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    input.span(),
+                    "VertexLayout can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "VertexLayout can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut attributes = Vec::new();
+    for field in fields.iter() {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let name = ident.to_string();
+        let format = match field_format(field)? {
+            FieldFormat::Skip => continue,
+            FieldFormat::Inferred => {
+                quote! { <#ty as ::gl_typed::VertexAttributeType>::FORMAT }
+            }
+            FieldFormat::Explicit(expr) => {
+                quote! { ::gl_typed::AttributeFormat::from(#expr) }
+            }
+        };
+        attributes.push(quote! {
+            ::gl_typed::VertexAttribute {
+                name: #name,
+                format: #format,
+                offset: (unsafe { ::std::ptr::addr_of!((*base_ptr).#ident) } as usize - base_ptr as usize) as u32,
+                byte_size: ::std::mem::size_of::<#ty>() as u32,
+            }
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::gl_typed::VertexLayout for #ident #ty_generics #where_clause {
+            fn attributes() -> ::std::vec::Vec<::gl_typed::VertexAttribute> {
+                let base = ::std::mem::MaybeUninit::<Self>::uninit();
+                let base_ptr = base.as_ptr();
+                ::std::vec![#(#attributes),*]
+            }
+        }
+    })
+}
+
+fn std140(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) if !fields.named.is_empty() => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    input.span(),
+                    "Std140 can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "Std140 can only be derived for structs",
+            ))
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "Std140 can not be derived for generic structs",
+        ));
+    }
+
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let padded_ident = format_ident!("{}Std140", ident);
+    let names: Vec<String> = fields
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap().to_string())
+        .collect();
+    let types: Vec<&syn::Type> = fields.iter().map(|field| &field.ty).collect();
+    let indices: Vec<usize> = (0..fields.len()).collect();
+
+    let mut padded_fields = Vec::new();
+    let mut conversions = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let field_ident = field.ident.as_ref().unwrap();
+        let field_vis = &field.vis;
+        let ty = &field.ty;
+        let padding_ident = format_ident!("_padding_{}", field_ident);
+        let next_offset = if index + 1 < fields.len() {
+            let next = index + 1;
+            quote! { <#ident as ::gl_typed::BlockStruct>::STD140_MEMBERS[#next].offset }
+        } else {
+            quote! { <#ident as ::gl_typed::BlockMember>::STD140.size }
+        };
+        let padding = quote! {
+            (#next_offset - <#ident as ::gl_typed::BlockStruct>::STD140_MEMBERS[#index].end()) as usize
+        };
+        padded_fields.push(quote! {
+            #field_vis #field_ident: <#ty as ::gl_typed::AsStd140>::Std140,
+            #padding_ident: [u8; #padding],
+        });
+        conversions.push(quote! {
+            #field_ident: ::gl_typed::AsStd140::as_std140(&self.#field_ident),
+            #padding_ident: [0; #padding],
+        });
+    }
+
+    let doc = format!("The std140 representation of `{}`.", ident);
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Debug, Copy, Clone)]
+        #[repr(C)]
+        #vis struct #padded_ident {
+            #(#padded_fields)*
+        }
+
+        impl ::gl_typed::BlockMember for #ident {
+            const STD140: ::gl_typed::TypeLayout = ::gl_typed::LayoutStandard::Std140.structure(&[
+                #(<#types as ::gl_typed::BlockMember>::STD140),*
+            ]);
+            const STD430: ::gl_typed::TypeLayout = ::gl_typed::LayoutStandard::Std430.structure(&[
+                #(<#types as ::gl_typed::BlockMember>::STD430),*
+            ]);
+
+            const AGGREGATE: bool = true;
+
+            fn block_variables(
+                standard: ::gl_typed::LayoutStandard,
+                name: &str,
+                offset: u32,
+                variables: &mut ::std::vec::Vec<::gl_typed::BlockVariableLayout>,
+            ) {
+                let members = match standard {
+                    ::gl_typed::LayoutStandard::Std140 => <Self as ::gl_typed::BlockStruct>::STD140_MEMBERS,
+                    ::gl_typed::LayoutStandard::Std430 => <Self as ::gl_typed::BlockStruct>::STD430_MEMBERS,
+                };
+                let join = |member: &str| {
+                    if name.is_empty() {
+                        ::std::string::String::from(member)
+                    } else {
+                        ::std::format!("{}.{}", name, member)
+                    }
+                };
+                #(
+                    <#types as ::gl_typed::BlockMember>::block_variables(
+                        standard,
+                        &join(#names),
+                        offset + members[#indices].offset,
+                        variables,
+                    );
+                )*
+            }
+        }
+
+        impl ::gl_typed::BlockArrayElement for #ident {}
+
+        impl ::gl_typed::BlockStruct for #ident {
+            const STD140_MEMBERS: &'static [::gl_typed::MemberLayout] = &::gl_typed::LayoutStandard::Std140.members(
+                [#(#names),*],
+                [#(<#types as ::gl_typed::BlockMember>::STD140),*],
+            );
+            const STD430_MEMBERS: &'static [::gl_typed::MemberLayout] = &::gl_typed::LayoutStandard::Std430.members(
+                [#(#names),*],
+                [#(<#types as ::gl_typed::BlockMember>::STD430),*],
+            );
+        }
+
+        impl ::gl_typed::AsStd140 for #ident {
+            type Std140 = #padded_ident;
+            type Std140Element = #padded_ident;
+
+            fn as_std140(&self) -> #padded_ident {
+                #padded_ident {
+                    #(#conversions)*
+                }
+            }
+
+            fn as_std140_element(&self) -> #padded_ident {
+                ::gl_typed::AsStd140::as_std140(self)
+            }
+        }
+    })
+}
@@ -0,0 +1,115 @@
+use gl_typed::*;
+
+#[derive(Std140)]
+#[allow(dead_code)]
+struct Light {
+    position: [f32; 3],
+    intensity: f32,
+}
+
+#[derive(Std140)]
+#[allow(dead_code)]
+struct Scene {
+    lights: [Light; 2],
+    view: ColMajorMatrix<[[f32; 3]; 3]>,
+    weights: [[f32; 1]; 2],
+}
+
+fn variable(
+    name: &str,
+    offset: u32,
+    array_stride: u32,
+    matrix_stride: u32,
+    is_row_major: bool,
+) -> BlockVariableLayout {
+    BlockVariableLayout {
+        name: String::from(name),
+        offset,
+        array_stride,
+        matrix_stride,
+        is_row_major,
+    }
+}
+
+fn uniform_block() -> ProgramBlockLayout {
+    ProgramBlockLayout {
+        name: String::from("Scene"),
+        data_size: 112,
+        variables: vec![
+            variable("lights[0].position", 0, 0, 0, false),
+            variable("lights[0].intensity", 12, 0, 0, false),
+            variable("lights[1].position", 16, 0, 0, false),
+            variable("lights[1].intensity", 28, 0, 0, false),
+            variable("view", 32, 0, 16, false),
+            variable("weights[0]", 80, 16, 0, false),
+        ],
+    }
+}
+
+#[test]
+fn flattens_like_gl() {
+    let mut variables = Vec::new();
+    Scene::block_variables(LayoutStandard::Std140, "", 0, &mut variables);
+    assert_eq!(uniform_block().variables, variables);
+}
+
+#[test]
+fn accepts_matching_blocks() {
+    assert_eq!(
+        Vec::<BlockLayoutMismatch>::new(),
+        diff_block_layout::<Scene>(LayoutStandard::Std140, &uniform_block())
+    );
+
+    // Shader storage blocks only report the first element of top-level
+    // arrays.
+    let storage_block = ProgramBlockLayout {
+        name: String::from("Scene"),
+        data_size: 88,
+        variables: vec![
+            variable("lights[0].position", 0, 0, 0, false),
+            variable("lights[0].intensity", 12, 0, 0, false),
+            variable("view", 32, 0, 16, false),
+            variable("weights[0]", 80, 4, 0, false),
+        ],
+    };
+    assert_eq!(
+        Vec::<BlockLayoutMismatch>::new(),
+        diff_block_layout::<Scene>(LayoutStandard::Std430, &storage_block)
+    );
+}
+
+#[test]
+fn reports_differences() {
+    let mut block = uniform_block();
+    block.data_size = 160;
+    block.variables[1].offset = 16;
+    block.variables[4].is_row_major = true;
+    block.variables[5] = variable("weights[0]", 80, 4, 0, false);
+    block.variables.push(variable("time", 96, 0, 0, false));
+    block.variables.retain(|v| !v.name.starts_with("view"));
+    assert_eq!(
+        vec![
+            BlockLayoutMismatch::DataSize {
+                expected: 112,
+                actual: 160,
+            },
+            BlockLayoutMismatch::Missing {
+                member: String::from("view"),
+            },
+            BlockLayoutMismatch::Offset {
+                variable: String::from("lights[0].intensity"),
+                expected: 12,
+                actual: 16,
+            },
+            BlockLayoutMismatch::ArrayStride {
+                variable: String::from("weights[0]"),
+                expected: 16,
+                actual: 4,
+            },
+            BlockLayoutMismatch::Unexpected {
+                variable: String::from("time"),
+            },
+        ],
+        diff_block_layout::<Scene>(LayoutStandard::Std140, &block)
+    );
+}
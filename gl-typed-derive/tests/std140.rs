@@ -0,0 +1,123 @@
+use gl_typed::*;
+
+#[derive(Std140, Copy, Clone)]
+struct Light {
+    position: [f32; 3],
+    intensity: f32,
+    color: [f32; 3],
+    enabled: bool,
+}
+
+#[derive(Std140)]
+struct Lights {
+    lights: [Light; 2],
+    view: ColMajorMatrix<[[f32; 3]; 3]>,
+    count: u32,
+    weights: [[f32; 1]; 2],
+}
+
+fn offsets(members: &[MemberLayout]) -> Vec<(&'static str, u32)> {
+    members.iter().map(|m| (m.name, m.offset)).collect()
+}
+
+#[test]
+fn computes_layouts() {
+    assert_eq!(
+        TypeLayout {
+            size: 32,
+            align: 16
+        },
+        Light::STD140
+    );
+    assert_eq!(
+        vec![
+            ("position", 0),
+            ("intensity", 12),
+            ("color", 16),
+            ("enabled", 28)
+        ],
+        offsets(Light::STD140_MEMBERS)
+    );
+
+    assert_eq!(
+        TypeLayout {
+            size: 160,
+            align: 16
+        },
+        Lights::STD140
+    );
+    assert_eq!(
+        vec![
+            ("lights", 0),
+            ("view", 64),
+            ("count", 112),
+            ("weights", 128)
+        ],
+        offsets(Lights::STD140_MEMBERS)
+    );
+
+    assert_eq!(
+        TypeLayout {
+            size: 128,
+            align: 16
+        },
+        Lights::STD430
+    );
+    assert_eq!(
+        vec![
+            ("lights", 0),
+            ("view", 64),
+            ("count", 112),
+            ("weights", 116)
+        ],
+        offsets(Lights::STD430_MEMBERS)
+    );
+}
+
+#[test]
+fn padded_struct_matches_layout() {
+    assert_eq!(32, std::mem::size_of::<LightStd140>());
+    assert_eq!(160, std::mem::size_of::<LightsStd140>());
+
+    let light = Light {
+        position: [1.0, 2.0, 3.0],
+        intensity: 4.0,
+        color: [5.0, 6.0, 7.0],
+        enabled: true,
+    };
+    let lights = Lights {
+        lights: [
+            light,
+            Light {
+                enabled: false,
+                ..light
+            },
+        ],
+        view: ColMajorMatrix([[8.0; 3]; 3]),
+        count: 2,
+        weights: [[9.0], [10.0]],
+    };
+    let padded = lights.as_std140();
+    let bytes: &[u8; 160] = unsafe { &*(&padded as *const LightsStd140 as *const [u8; 160]) };
+    let word = |offset: usize| {
+        let mut word = [0; 4];
+        word.copy_from_slice(&bytes[offset..offset + 4]);
+        word
+    };
+    let float = |offset: usize| f32::from_ne_bytes(word(offset));
+    let uint = |offset: usize| u32::from_ne_bytes(word(offset));
+
+    assert_eq!(3.0, float(8));
+    assert_eq!(4.0, float(12));
+    assert_eq!(5.0, float(16));
+    assert_eq!(1, uint(28));
+    assert_eq!(1.0, float(32));
+    assert_eq!(0, uint(60));
+    assert_eq!(8.0, float(64));
+    assert_eq!(0.0, float(76));
+    assert_eq!(8.0, float(80));
+    assert_eq!(2, uint(112));
+    assert_eq!(0, uint(116));
+    assert_eq!(9.0, float(128));
+    assert_eq!(10.0, float(144));
+}
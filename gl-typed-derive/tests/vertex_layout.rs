@@ -0,0 +1,64 @@
+use gl_typed::*;
+
+#[derive(VertexLayout)]
+#[repr(C)]
+#[allow(dead_code)]
+struct Vertex {
+    position: [f32; 3],
+    #[vertex(format = "AttributeFormatF::U8(ComponentCount::P4, true)")]
+    color: [u8; 4],
+    #[vertex(skip)]
+    padding: u32,
+    tex_coord: [f32; 2],
+    material: u16,
+}
+
+#[derive(VertexLayout)]
+#[allow(dead_code)]
+struct Generic<T: VertexAttributeType> {
+    value: T,
+}
+
+#[test]
+fn derives_formats_and_offsets() {
+    assert_eq!(
+        Vertex::attributes(),
+        vec![
+            VertexAttribute {
+                name: "position",
+                format: AttributeFormat::F(AttributeFormatF::F32(ComponentCount::P3)),
+                offset: 0,
+                byte_size: 12,
+            },
+            VertexAttribute {
+                name: "color",
+                format: AttributeFormat::F(AttributeFormatF::U8(ComponentCount::P4, true)),
+                offset: 12,
+                byte_size: 4,
+            },
+            VertexAttribute {
+                name: "tex_coord",
+                format: AttributeFormat::F(AttributeFormatF::F32(ComponentCount::P2)),
+                offset: 20,
+                byte_size: 8,
+            },
+            VertexAttribute {
+                name: "material",
+                format: AttributeFormat::I(AttributeFormatI::U16(ComponentCount::P1)),
+                offset: 28,
+                byte_size: 2,
+            },
+        ]
+    );
+    assert_eq!(32, Vertex::stride());
+}
+
+#[test]
+fn derives_for_generic_structs() {
+    let attributes = Generic::<[i32; 2]>::attributes();
+    assert_eq!(1, attributes.len());
+    assert_eq!(
+        AttributeFormat::I(AttributeFormatI::I32(ComponentCount::P2)),
+        attributes[0].format
+    );
+}
@@ -42,8 +42,8 @@ pub enum AttributeFormatF {
     U16(ComponentCount, bool),
     I32(ComponentCount, bool),
     U32(ComponentCount, bool),
-    I_2_10_10_10_REV,
-    U_2_10_10_10_REV,
+    I_2_10_10_10_REV(bool),
+    U_2_10_10_10_REV(bool),
     U_10F_11F_11F_REV,
 }
 
@@ -56,8 +56,7 @@ impl AttributeFormatF {
             Self::I32(c, _) | Self::U32(c, _) | Self::F32(c) | Self::Fixed16_16(c) => {
                 c.to_u32() * 4
             }
-            Self::I_2_10_10_10_REV | Self::U_2_10_10_10_REV => 4,
-            Self::U_10F_11F_11F_REV => 3,
+            Self::I_2_10_10_10_REV(_) | Self::U_2_10_10_10_REV(_) | Self::U_10F_11F_11F_REV => 4,
         }
     }
 
@@ -69,13 +68,10 @@ impl AttributeFormatF {
             | Self::I16(_, n)
             | Self::U16(_, n)
             | Self::I32(_, n)
-            | Self::U32(_, n) => n,
-            Self::F16(_)
-            | Self::F32(_)
-            | Self::Fixed16_16(_)
-            | Self::I_2_10_10_10_REV
-            | Self::U_2_10_10_10_REV
-            | Self::U_10F_11F_11F_REV => false,
+            | Self::U32(_, n)
+            | Self::I_2_10_10_10_REV(n)
+            | Self::U_2_10_10_10_REV(n) => n,
+            Self::F16(_) | Self::F32(_) | Self::Fixed16_16(_) | Self::U_10F_11F_11F_REV => false,
         }
     }
 
@@ -91,7 +87,7 @@ impl AttributeFormatF {
             | Self::U16(c, _)
             | Self::I32(c, _)
             | Self::U32(c, _) => c,
-            Self::I_2_10_10_10_REV | Self::U_2_10_10_10_REV => ComponentCount::P4,
+            Self::I_2_10_10_10_REV(_) | Self::U_2_10_10_10_REV(_) => ComponentCount::P4,
             Self::U_10F_11F_11F_REV => ComponentCount::P3,
         }
     }
@@ -108,8 +104,8 @@ impl AttributeFormatF {
             Self::U16(_, _) => gl::UNSIGNED_SHORT,
             Self::I32(_, _) => gl::INT,
             Self::U32(_, _) => gl::UNSIGNED_INT,
-            Self::I_2_10_10_10_REV => gl::INT_2_10_10_10_REV,
-            Self::U_2_10_10_10_REV => gl::UNSIGNED_INT_2_10_10_10_REV,
+            Self::I_2_10_10_10_REV(_) => gl::INT_2_10_10_10_REV,
+            Self::U_2_10_10_10_REV(_) => gl::UNSIGNED_INT_2_10_10_10_REV,
             Self::U_10F_11F_11F_REV => gl::UNSIGNED_INT_10F_11F_11F_REV,
         }
     }
@@ -205,6 +201,67 @@ impl AttributeFormat {
             Self::L(a) => a.byte_size(),
         }
     }
+
+    #[inline]
+    pub fn component_count(&self) -> ComponentCount {
+        match *self {
+            Self::F(a) => a.component_count(),
+            Self::I(a) => a.component_count(),
+            Self::L(a) => a.component_count(),
+        }
+    }
+
+    /// Reconstructs the format from the vertex array state. A size of `BGRA`
+    /// is reported as 4 components. Returns `None` for combinations that can
+    /// not be expressed, like double components converted to floats.
+    pub fn from_vertex_array_state(
+        size: u32,
+        component_type: u32,
+        normalized: bool,
+        integer: bool,
+        long: bool,
+    ) -> Option<Self> {
+        let c = match size {
+            1 => ComponentCount::P1,
+            2 => ComponentCount::P2,
+            3 => ComponentCount::P3,
+            4 | gl::BGRA => ComponentCount::P4,
+            _ => return None,
+        };
+        let n = normalized;
+        Some(if long {
+            match component_type {
+                gl::DOUBLE => AttributeFormatL::F64(c).into(),
+                _ => return None,
+            }
+        } else if integer {
+            match component_type {
+                gl::BYTE => AttributeFormatI::I8(c).into(),
+                gl::UNSIGNED_BYTE => AttributeFormatI::U8(c).into(),
+                gl::SHORT => AttributeFormatI::I16(c).into(),
+                gl::UNSIGNED_SHORT => AttributeFormatI::U16(c).into(),
+                gl::INT => AttributeFormatI::I32(c).into(),
+                gl::UNSIGNED_INT => AttributeFormatI::U32(c).into(),
+                _ => return None,
+            }
+        } else {
+            match component_type {
+                gl::HALF_FLOAT => AttributeFormatF::F16(c).into(),
+                gl::FLOAT => AttributeFormatF::F32(c).into(),
+                gl::FIXED => AttributeFormatF::Fixed16_16(c).into(),
+                gl::BYTE => AttributeFormatF::I8(c, n).into(),
+                gl::UNSIGNED_BYTE => AttributeFormatF::U8(c, n).into(),
+                gl::SHORT => AttributeFormatF::I16(c, n).into(),
+                gl::UNSIGNED_SHORT => AttributeFormatF::U16(c, n).into(),
+                gl::INT => AttributeFormatF::I32(c, n).into(),
+                gl::UNSIGNED_INT => AttributeFormatF::U32(c, n).into(),
+                gl::INT_2_10_10_10_REV => AttributeFormatF::I_2_10_10_10_REV(n).into(),
+                gl::UNSIGNED_INT_2_10_10_10_REV => AttributeFormatF::U_2_10_10_10_REV(n).into(),
+                gl::UNSIGNED_INT_10F_11F_11F_REV => AttributeFormatF::U_10F_11F_11F_REV.into(),
+                _ => return None,
+            }
+        })
+    }
 }
 
 impl From<AttributeFormatF> for AttributeFormat {
@@ -226,6 +283,31 @@ impl From<AttributeFormatL> for AttributeFormat {
 }
 
 impl Gl {
+    /// Reads back the format set with `vertex_array_attrib_format`. See
+    /// `AttributeFormat::from_vertex_array_state`.
+    #[inline]
+    pub unsafe fn get_vertex_array_attrib_format(
+        &self,
+        vertex_array_name: VertexArrayName,
+        location: AttributeLocation,
+    ) -> Option<AttributeFormat> {
+        AttributeFormat::from_vertex_array_state(
+            self.get_vertex_array_indexediv(vertex_array_name, location, VERTEX_ATTRIB_ARRAY_SIZE),
+            self.get_vertex_array_indexediv(vertex_array_name, location, VERTEX_ATTRIB_ARRAY_TYPE),
+            self.get_vertex_array_indexediv(
+                vertex_array_name,
+                location,
+                VERTEX_ATTRIB_ARRAY_NORMALIZED,
+            ),
+            self.get_vertex_array_indexediv(
+                vertex_array_name,
+                location,
+                VERTEX_ATTRIB_ARRAY_INTEGER,
+            ),
+            self.get_vertex_array_indexediv(vertex_array_name, location, VERTEX_ATTRIB_ARRAY_LONG),
+        )
+    }
+
     #[inline]
     pub unsafe fn vertex_array_attrib_format(
         &self,
@@ -0,0 +1,412 @@
+use crate::*;
+use std::fmt;
+
+/// A uniform or shader storage block of a program.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ProgramBlockIndex {
+    Uniform(UniformBlockIndex),
+    ShaderStorage(ShaderStorageBlockIndex),
+}
+
+impl From<UniformBlockIndex> for ProgramBlockIndex {
+    #[inline]
+    fn from(index: UniformBlockIndex) -> Self {
+        ProgramBlockIndex::Uniform(index)
+    }
+}
+
+impl From<ShaderStorageBlockIndex> for ProgramBlockIndex {
+    #[inline]
+    fn from(index: ShaderStorageBlockIndex) -> Self {
+        ProgramBlockIndex::ShaderStorage(index)
+    }
+}
+
+impl ProgramBlockIndex {
+    #[inline]
+    pub fn interface(self) -> ProgramInterface {
+        match self {
+            ProgramBlockIndex::Uniform(_) => ProgramInterface::UniformBlock,
+            ProgramBlockIndex::ShaderStorage(_) => ProgramInterface::ShaderStorageBlock,
+        }
+    }
+
+    /// The interface the members of the block belong to.
+    #[inline]
+    pub fn variable_interface(self) -> ProgramInterface {
+        match self {
+            ProgramBlockIndex::Uniform(_) => ProgramInterface::Uniform,
+            ProgramBlockIndex::ShaderStorage(_) => ProgramInterface::BufferVariable,
+        }
+    }
+
+    #[inline]
+    pub fn to_u32(self) -> u32 {
+        match self {
+            ProgramBlockIndex::Uniform(index) => index.to_u32(),
+            ProgramBlockIndex::ShaderStorage(index) => index.to_u32(),
+        }
+    }
+}
+
+/// The layout of a block as reported by GL.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProgramBlockLayout {
+    pub name: String,
+    pub data_size: u32,
+    /// Sorted by offset. The names do not include the block name.
+    pub variables: Vec<BlockVariableLayout>,
+}
+
+/// An atomic counter uniform as reported by GL.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProgramAtomicCounter {
+    pub name: String,
+    pub offset: u32,
+    pub array_size: u32,
+}
+
+/// An active atomic counter buffer binding point of a program.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProgramAtomicCounterBuffer {
+    pub binding: u32,
+    pub data_size: usize,
+    /// Sorted by offset.
+    pub counters: Vec<ProgramAtomicCounter>,
+}
+
+/// A difference between the layout of a Rust type and a block.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BlockLayoutMismatch {
+    /// The Rust type is smaller than the block.
+    DataSize { expected: u32, actual: u32 },
+    /// A member of the Rust type has no counterpart in the block.
+    Missing { member: String },
+    /// A variable of the block has no counterpart in the Rust type.
+    Unexpected { variable: String },
+    Offset {
+        variable: String,
+        expected: u32,
+        actual: u32,
+    },
+    ArrayStride {
+        variable: String,
+        expected: u32,
+        actual: u32,
+    },
+    MatrixStride {
+        variable: String,
+        expected: u32,
+        actual: u32,
+    },
+    RowMajor {
+        variable: String,
+        expected: bool,
+        actual: bool,
+    },
+}
+
+impl fmt::Display for BlockLayoutMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlockLayoutMismatch::DataSize { expected, actual } => write!(
+                f,
+                "The block requires {} bytes but the Rust type has {}.",
+                actual, expected
+            ),
+            BlockLayoutMismatch::Missing { member } => {
+                write!(f, "Member {} does not exist in the block.", member)
+            }
+            BlockLayoutMismatch::Unexpected { variable } => {
+                write!(f, "Variable {} does not exist in the Rust type.", variable)
+            }
+            BlockLayoutMismatch::Offset {
+                variable,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Variable {} is at offset {} but the Rust type places it at {}.",
+                variable, actual, expected
+            ),
+            BlockLayoutMismatch::ArrayStride {
+                variable,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Variable {} has array stride {} but the Rust type uses {}.",
+                variable, actual, expected
+            ),
+            BlockLayoutMismatch::MatrixStride {
+                variable,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Variable {} has matrix stride {} but the Rust type uses {}.",
+                variable, actual, expected
+            ),
+            BlockLayoutMismatch::RowMajor {
+                variable,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Variable {} is {} but the Rust type is {}.",
+                variable,
+                if *actual { "row major" } else { "column major" },
+                if *expected {
+                    "row major"
+                } else {
+                    "column major"
+                }
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BlockLayoutMismatch {}
+
+/// Compares the layout of `T` under `standard` with a block layout read from
+/// GL. Variables that GL does not report, like the elements after the first
+/// of a top-level array in a shader storage block, are not checked.
+pub fn diff_block_layout<T: BlockStruct>(
+    standard: LayoutStandard,
+    actual: &ProgramBlockLayout,
+) -> Vec<BlockLayoutMismatch> {
+    let mut mismatches = Vec::new();
+
+    let expected_size = standard.layout_of::<T>().size;
+    if expected_size < actual.data_size {
+        mismatches.push(BlockLayoutMismatch::DataSize {
+            expected: expected_size,
+            actual: actual.data_size,
+        });
+    }
+
+    let members = match standard {
+        LayoutStandard::Std140 => T::STD140_MEMBERS,
+        LayoutStandard::Std430 => T::STD430_MEMBERS,
+    };
+    for member in members {
+        let found = actual.variables.iter().any(|variable| {
+            let rest = match variable.name.strip_prefix(member.name) {
+                Some(rest) => rest,
+                None => return false,
+            };
+            rest.is_empty() || rest.starts_with('[') || rest.starts_with('.')
+        });
+        if !found {
+            mismatches.push(BlockLayoutMismatch::Missing {
+                member: String::from(member.name),
+            });
+        }
+    }
+
+    let mut expected_variables = Vec::new();
+    T::block_variables(standard, "", 0, &mut expected_variables);
+    for variable in actual.variables.iter() {
+        let expected = match expected_variables
+            .iter()
+            .find(|expected| expected.name == variable.name)
+        {
+            Some(expected) => expected,
+            None => {
+                mismatches.push(BlockLayoutMismatch::Unexpected {
+                    variable: variable.name.clone(),
+                });
+                continue;
+            }
+        };
+        if expected.offset != variable.offset {
+            mismatches.push(BlockLayoutMismatch::Offset {
+                variable: variable.name.clone(),
+                expected: expected.offset,
+                actual: variable.offset,
+            });
+        }
+        if expected.array_stride != variable.array_stride {
+            mismatches.push(BlockLayoutMismatch::ArrayStride {
+                variable: variable.name.clone(),
+                expected: expected.array_stride,
+                actual: variable.array_stride,
+            });
+        }
+        if expected.matrix_stride != variable.matrix_stride {
+            mismatches.push(BlockLayoutMismatch::MatrixStride {
+                variable: variable.name.clone(),
+                expected: expected.matrix_stride,
+                actual: variable.matrix_stride,
+            });
+        }
+        if expected.is_row_major != variable.is_row_major {
+            mismatches.push(BlockLayoutMismatch::RowMajor {
+                variable: variable.name.clone(),
+                expected: expected.is_row_major,
+                actual: variable.is_row_major,
+            });
+        }
+    }
+
+    mismatches
+}
+
+impl Gl {
+    /// Reads the data size of a block and the offset and strides of each of
+    /// its variables.
+    pub unsafe fn get_program_block_layout<B>(
+        &self,
+        program_name: ProgramName,
+        block: B,
+    ) -> ProgramBlockLayout
+    where
+        B: Into<ProgramBlockIndex>,
+    {
+        let block = block.into();
+        let mut values = [0; 2];
+        self.get_program_resourceiv(
+            program_name,
+            block.interface(),
+            block.to_u32(),
+            &[
+                ProgramResourceProperty::BufferDataSize,
+                ProgramResourceProperty::NumActiveVariables,
+            ],
+            &mut values,
+        );
+        let [data_size, variable_count] = values;
+        let mut indices = vec![0; variable_count as usize];
+        self.get_program_resourceiv(
+            program_name,
+            block.interface(),
+            block.to_u32(),
+            &[ProgramResourceProperty::ActiveVariables],
+            &mut indices,
+        );
+
+        let name = self.get_program_resource_name(program_name, block.interface(), block.to_u32());
+        // Members of an instanced block are prefixed with the block name
+        // without the array index of the instance.
+        let prefix = format!("{}.", name.split('[').next().unwrap());
+
+        let mut variables: Vec<BlockVariableLayout> = indices
+            .into_iter()
+            .map(|index| {
+                let mut values = [0; 4];
+                self.get_program_resourceiv(
+                    program_name,
+                    block.variable_interface(),
+                    index as u32,
+                    &[
+                        ProgramResourceProperty::Offset,
+                        ProgramResourceProperty::ArrayStride,
+                        ProgramResourceProperty::MatrixStride,
+                        ProgramResourceProperty::IsRowMajor,
+                    ],
+                    &mut values,
+                );
+                let [offset, array_stride, matrix_stride, is_row_major] = values;
+                let name = self.get_program_resource_name(
+                    program_name,
+                    block.variable_interface(),
+                    index as u32,
+                );
+                BlockVariableLayout {
+                    name: match name.strip_prefix(&prefix) {
+                        Some(name) => String::from(name),
+                        None => name,
+                    },
+                    offset: offset as u32,
+                    array_stride: array_stride as u32,
+                    matrix_stride: matrix_stride as u32,
+                    is_row_major: is_row_major != 0,
+                }
+            })
+            .collect();
+        variables.sort_by_key(|variable| variable.offset);
+
+        ProgramBlockLayout {
+            name,
+            data_size: data_size as u32,
+            variables,
+        }
+    }
+
+    /// Reads the binding, size and counters of every atomic counter buffer
+    /// used by a program.
+    pub unsafe fn get_program_atomic_counter_buffers(
+        &self,
+        program_name: ProgramName,
+    ) -> Vec<ProgramAtomicCounterBuffer> {
+        let count = self.get_programiv(program_name, ACTIVE_ATOMIC_COUNTER_BUFFERS);
+        (0..count)
+            .map(|buffer_index| {
+                let mut counters: Vec<ProgramAtomicCounter> = self
+                    .get_active_atomic_counter_buffer_counter_indices(program_name, buffer_index)
+                    .into_iter()
+                    .map(|index| {
+                        let mut values = [0; 2];
+                        self.get_program_resourceiv(
+                            program_name,
+                            ProgramInterface::Uniform,
+                            index,
+                            &[
+                                ProgramResourceProperty::Offset,
+                                ProgramResourceProperty::ArraySize,
+                            ],
+                            &mut values,
+                        );
+                        let [offset, array_size] = values;
+                        ProgramAtomicCounter {
+                            name: self.get_program_resource_name(
+                                program_name,
+                                ProgramInterface::Uniform,
+                                index,
+                            ),
+                            offset: offset as u32,
+                            array_size: array_size as u32,
+                        }
+                    })
+                    .collect();
+                counters.sort_by_key(|counter| counter.offset);
+                ProgramAtomicCounterBuffer {
+                    binding: self.get_active_atomic_counter_bufferiv(
+                        program_name,
+                        buffer_index,
+                        ATOMIC_COUNTER_BUFFER_BINDING,
+                    ),
+                    data_size: self.get_active_atomic_counter_bufferiv(
+                        program_name,
+                        buffer_index,
+                        ATOMIC_COUNTER_BUFFER_DATA_SIZE,
+                    ),
+                    counters,
+                }
+            })
+            .collect()
+    }
+
+    /// Compares the layout of `T` under `standard` with the layout of a block
+    /// in a linked program. `standard` has to match the layout qualifier of
+    /// the block, for example `Std140` for a `layout(std140) buffer` block.
+    pub unsafe fn validate_block_layout<T, B>(
+        &self,
+        program_name: ProgramName,
+        block: B,
+        standard: LayoutStandard,
+    ) -> Result<(), Vec<BlockLayoutMismatch>>
+    where
+        T: BlockStruct,
+        B: Into<ProgramBlockIndex>,
+    {
+        let block = block.into();
+        let layout = self.get_program_block_layout(program_name, block);
+        let mismatches = diff_block_layout::<T>(standard, &layout);
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+}
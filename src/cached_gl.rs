@@ -0,0 +1,626 @@
+//! An optional wrapper around `Gl` that keeps a shadow copy of the most common
+//! binding points and skips calls that would not change them.
+
+use crate::*;
+use std::cell::{Cell, RefCell};
+
+const BUFFER_TARGET_COUNT: usize = 15;
+
+#[inline]
+fn buffer_target_index(target: BufferTarget) -> usize {
+    match target {
+        BufferTarget::ArrayBuffer => 0,
+        BufferTarget::AtomicCounterBuffer => 1,
+        BufferTarget::CopyReadBuffer => 2,
+        BufferTarget::CopyWriteBuffer => 3,
+        BufferTarget::DispatchIndirectBuffer => 4,
+        BufferTarget::DrawIndirectBuffer => 5,
+        BufferTarget::ElementArrayBuffer => 6,
+        BufferTarget::ParameterBuffer => 7,
+        BufferTarget::PixelPackBuffer => 8,
+        BufferTarget::PixelUnpackBuffer => 9,
+        BufferTarget::QueryBuffer => 10,
+        BufferTarget::ShaderStorageBuffer => 11,
+        BufferTarget::TextureBuffer => 12,
+        BufferTarget::TransformFeedbackBuffer => 13,
+        BufferTarget::UniformBuffer => 14,
+    }
+}
+
+#[inline]
+fn bind_buffer_target_index(target: BindBufferTarget) -> usize {
+    buffer_target_index(match target {
+        BindBufferTarget::AtomicCounterBuffer => BufferTarget::AtomicCounterBuffer,
+        BindBufferTarget::TransformFeedbackBuffer => BufferTarget::TransformFeedbackBuffer,
+        BindBufferTarget::UniformBuffer => BufferTarget::UniformBuffer,
+        BindBufferTarget::ShaderStorageBuffer => BufferTarget::ShaderStorageBuffer,
+    })
+}
+
+/// Returns true if the slot changed. `None` means the value is unknown.
+#[inline]
+fn update(slot: &mut Option<u32>, name: u32) -> bool {
+    if *slot == Some(name) {
+        false
+    } else {
+        *slot = Some(name);
+        true
+    }
+}
+
+#[inline]
+fn update_unit(slots: &mut Vec<Option<u32>>, unit: u32, name: u32) -> bool {
+    let unit = unit as usize;
+    if unit >= slots.len() {
+        slots.resize(unit + 1, None);
+    }
+    update(&mut slots[unit], name)
+}
+
+/// Deleting an object resets every binding point it is bound to to 0.
+#[inline]
+fn forget(slot: &mut Option<u32>, name: u32) {
+    if *slot == Some(name) {
+        *slot = Some(0);
+    }
+}
+
+#[derive(Debug, Default)]
+struct BindingCache {
+    buffers: [Option<u32>; BUFFER_TARGET_COUNT],
+    texture_units: Vec<Option<u32>>,
+    sampler_units: Vec<Option<u32>>,
+    program: Option<u32>,
+    vertex_array: Option<u32>,
+    draw_framebuffer: Option<u32>,
+    read_framebuffer: Option<u32>,
+}
+
+impl BindingCache {
+    fn bind_buffer(&mut self, target: BufferTarget, name: u32) -> bool {
+        update(&mut self.buffers[buffer_target_index(target)], name)
+    }
+
+    fn bind_vertex_array(&mut self, name: u32) -> bool {
+        let changed = update(&mut self.vertex_array, name);
+        if changed {
+            // The element array buffer binding is part of the vertex array.
+            self.buffers[buffer_target_index(BufferTarget::ElementArrayBuffer)] = None;
+        }
+        changed
+    }
+
+    fn bind_framebuffer(&mut self, target: FramebufferTarget, name: u32) -> bool {
+        match target {
+            FramebufferTarget::DrawFramebuffer => update(&mut self.draw_framebuffer, name),
+            FramebufferTarget::ReadFramebuffer => update(&mut self.read_framebuffer, name),
+            FramebufferTarget::Framebuffer => {
+                let draw = update(&mut self.draw_framebuffer, name);
+                let read = update(&mut self.read_framebuffer, name);
+                draw || read
+            }
+        }
+    }
+
+    fn delete_buffer(&mut self, name: u32) {
+        for slot in self.buffers.iter_mut() {
+            forget(slot, name);
+        }
+    }
+
+    fn delete_texture(&mut self, name: u32) {
+        for slot in self.texture_units.iter_mut() {
+            forget(slot, name);
+        }
+    }
+
+    fn delete_sampler(&mut self, name: u32) {
+        for slot in self.sampler_units.iter_mut() {
+            forget(slot, name);
+        }
+    }
+
+    fn delete_vertex_array(&mut self, name: u32) {
+        if self.vertex_array == Some(name) {
+            self.bind_vertex_array(0);
+        }
+    }
+
+    fn delete_framebuffer(&mut self, name: u32) {
+        forget(&mut self.draw_framebuffer, name);
+        forget(&mut self.read_framebuffer, name);
+    }
+}
+
+/// The number of binding calls that were passed on to and skipped before
+/// reaching the driver.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct CachedGlStats {
+    pub issued: u64,
+    pub elided: u64,
+}
+
+/// Wraps a `Gl` and skips binding calls whose value is already current.
+///
+/// All other functions are available through `gl`. After changing any of the
+/// cached bindings without going through the `CachedGl`, for example through
+/// the wrapped `Gl` or in external code, call `invalidate`.
+pub struct CachedGl {
+    gl: Gl,
+    cache: RefCell<BindingCache>,
+    stats: Cell<CachedGlStats>,
+}
+
+impl CachedGl {
+    /// Nothing is assumed about the current bindings.
+    #[inline]
+    pub fn new(gl: Gl) -> Self {
+        CachedGl {
+            gl,
+            cache: RefCell::new(BindingCache::default()),
+            stats: Cell::new(CachedGlStats::default()),
+        }
+    }
+
+    /// The wrapped `Gl`. Bindings changed through it are not tracked, call
+    /// `invalidate` afterwards.
+    #[inline]
+    pub fn gl(&self) -> &Gl {
+        &self.gl
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> Gl {
+        self.gl
+    }
+
+    /// Forget all cached bindings so the next binding calls are issued.
+    #[inline]
+    pub fn invalidate(&self) {
+        *self.cache.borrow_mut() = BindingCache::default();
+    }
+
+    #[inline]
+    pub fn stats(&self) -> CachedGlStats {
+        self.stats.get()
+    }
+
+    #[inline]
+    pub fn reset_stats(&self) {
+        self.stats.set(CachedGlStats::default());
+    }
+
+    #[inline]
+    fn count(&self, issue: bool) -> bool {
+        let mut stats = self.stats.get();
+        if issue {
+            stats.issued += 1;
+        } else {
+            stats.elided += 1;
+        }
+        self.stats.set(stats);
+        issue
+    }
+
+    #[inline]
+    pub unsafe fn bind_buffer<T>(&self, target: T, name: BufferName)
+    where
+        T: Into<BufferTarget>,
+    {
+        let target = target.into();
+        if self.count(self.cache.borrow_mut().bind_buffer(target, name.to_u32())) {
+            self.gl.bind_buffer(target, name);
+        }
+    }
+
+    #[inline]
+    pub unsafe fn unbind_buffer<T>(&self, target: T)
+    where
+        T: Into<BufferTarget>,
+    {
+        let target = target.into();
+        if self.count(self.cache.borrow_mut().bind_buffer(target, 0)) {
+            self.gl.unbind_buffer(target);
+        }
+    }
+
+    /// Always issued. Also updates the cached generic binding of `target`.
+    #[inline]
+    pub unsafe fn bind_buffer_base<T>(&self, target: T, index: u32, buffer_name: BufferName)
+    where
+        T: Into<BindBufferTarget>,
+    {
+        let target = target.into();
+        self.cache.borrow_mut().buffers[bind_buffer_target_index(target)] =
+            Some(buffer_name.to_u32());
+        self.count(true);
+        self.gl.bind_buffer_base(target, index, buffer_name);
+    }
+
+    /// Always issued. Also updates the cached generic binding of `target`.
+    #[inline]
+    pub unsafe fn bind_buffer_range<T>(
+        &self,
+        target: T,
+        index: u32,
+        buffer_name: BufferName,
+        offset: usize,
+        size: usize,
+    ) where
+        T: Into<BindBufferTarget>,
+    {
+        let target = target.into();
+        self.cache.borrow_mut().buffers[bind_buffer_target_index(target)] =
+            Some(buffer_name.to_u32());
+        self.count(true);
+        self.gl
+            .bind_buffer_range(target, index, buffer_name, offset, size);
+    }
+
+    #[inline]
+    pub unsafe fn bind_texture_unit(&self, unit: u32, texture_name: TextureName) {
+        let changed = update_unit(
+            &mut self.cache.borrow_mut().texture_units,
+            unit,
+            texture_name.to_u32(),
+        );
+        if self.count(changed) {
+            self.gl.bind_texture_unit(unit, texture_name);
+        }
+    }
+
+    /// Always issued. Updates the cached bindings of every unit in the range.
+    #[inline]
+    pub unsafe fn bind_textures(&self, first_unit: u32, texture_names: &[Option<TextureName>]) {
+        {
+            let mut cache = self.cache.borrow_mut();
+            for (unit, name) in (first_unit..).zip(texture_names.iter()) {
+                update_unit(
+                    &mut cache.texture_units,
+                    unit,
+                    name.map_or(0, |name| name.to_u32()),
+                );
+            }
+        }
+        self.count(true);
+        self.gl.bind_textures(first_unit, texture_names);
+    }
+
+    /// Always issued. Binds to the active texture unit, which is not tracked,
+    /// so all cached texture unit bindings are forgotten.
+    #[inline]
+    pub unsafe fn bind_texture<T>(&self, target: T, name: TextureName)
+    where
+        T: Into<TextureTarget>,
+    {
+        self.cache.borrow_mut().texture_units.clear();
+        self.count(true);
+        self.gl.bind_texture(target, name);
+    }
+
+    /// Always issued. See `bind_texture`.
+    #[inline]
+    pub unsafe fn unbind_texture<T>(&self, target: T)
+    where
+        T: Into<TextureTarget>,
+    {
+        self.cache.borrow_mut().texture_units.clear();
+        self.count(true);
+        self.gl.unbind_texture(target);
+    }
+
+    #[inline]
+    pub unsafe fn bind_sampler(&self, unit: u32, name: SamplerName) {
+        let changed = update_unit(
+            &mut self.cache.borrow_mut().sampler_units,
+            unit,
+            name.to_u32(),
+        );
+        if self.count(changed) {
+            self.gl.bind_sampler(unit, name);
+        }
+    }
+
+    /// Always issued. Updates the cached bindings of every unit in the range.
+    #[inline]
+    pub unsafe fn bind_samplers(&self, first_unit: u32, count: u32, names: &[SamplerName]) {
+        {
+            let mut cache = self.cache.borrow_mut();
+            for (unit, name) in (first_unit..).zip(names.iter().take(count as usize)) {
+                update_unit(&mut cache.sampler_units, unit, name.to_u32());
+            }
+        }
+        self.count(true);
+        self.gl.bind_samplers(first_unit, count, names);
+    }
+
+    #[inline]
+    pub unsafe fn unbind_sampler(&self, unit: u32) {
+        let changed = update_unit(&mut self.cache.borrow_mut().sampler_units, unit, 0);
+        if self.count(changed) {
+            self.gl.unbind_sampler(unit);
+        }
+    }
+
+    #[inline]
+    pub unsafe fn use_program(&self, program_name: ProgramName) {
+        let changed = update(&mut self.cache.borrow_mut().program, program_name.to_u32());
+        if self.count(changed) {
+            self.gl.use_program(program_name);
+        }
+    }
+
+    #[inline]
+    pub unsafe fn unuse_program(&self) {
+        let changed = update(&mut self.cache.borrow_mut().program, 0);
+        if self.count(changed) {
+            self.gl.unuse_program();
+        }
+    }
+
+    #[inline]
+    pub unsafe fn bind_vertex_array(&self, vertex_array_name: VertexArrayName) {
+        let changed = self
+            .cache
+            .borrow_mut()
+            .bind_vertex_array(vertex_array_name.to_u32());
+        if self.count(changed) {
+            self.gl.bind_vertex_array(vertex_array_name);
+        }
+    }
+
+    #[inline]
+    pub unsafe fn unbind_vertex_array(&self) {
+        let changed = self.cache.borrow_mut().bind_vertex_array(0);
+        if self.count(changed) {
+            self.gl.unbind_vertex_array();
+        }
+    }
+
+    #[inline]
+    pub unsafe fn bind_framebuffer<T, N>(&self, target: T, name: N)
+    where
+        T: Into<FramebufferTarget>,
+        N: Into<FramebufferName>,
+    {
+        let target = target.into();
+        let name = name.into();
+        let changed = self
+            .cache
+            .borrow_mut()
+            .bind_framebuffer(target, name.to_u32());
+        if self.count(changed) {
+            self.gl.bind_framebuffer(target, name);
+        }
+    }
+
+    /// Always issued. Updates the cached `DRAW_INDIRECT_BUFFER` binding.
+    #[inline]
+    pub unsafe fn multi_draw_arrays_indirect_commands<M>(
+        &self,
+        mode: M,
+        buffer_name: BufferName,
+        byte_offset: usize,
+        commands: &[DrawArraysIndirectCommand],
+    ) where
+        M: Into<DrawMode>,
+    {
+        self.cache
+            .borrow_mut()
+            .bind_buffer(BufferTarget::DrawIndirectBuffer, buffer_name.to_u32());
+        self.count(true);
+        self.gl
+            .multi_draw_arrays_indirect_commands(mode, buffer_name, byte_offset, commands);
+    }
+
+    /// Always issued. Updates the cached `DRAW_INDIRECT_BUFFER` binding.
+    #[inline]
+    pub unsafe fn multi_draw_elements_indirect_commands<M, T>(
+        &self,
+        mode: M,
+        ty: T,
+        buffer_name: BufferName,
+        byte_offset: usize,
+        commands: &[DrawElementsIndirectCommand],
+    ) where
+        M: Into<DrawMode>,
+        T: Into<DrawElementsType>,
+    {
+        self.cache
+            .borrow_mut()
+            .bind_buffer(BufferTarget::DrawIndirectBuffer, buffer_name.to_u32());
+        self.count(true);
+        self.gl
+            .multi_draw_elements_indirect_commands(mode, ty, buffer_name, byte_offset, commands);
+    }
+
+    /// Always issued. Updates the cached `DISPATCH_INDIRECT_BUFFER` binding.
+    #[inline]
+    pub unsafe fn dispatch_compute_indirect_command(
+        &self,
+        buffer_name: BufferName,
+        byte_offset: usize,
+        command: &DispatchIndirectCommand,
+    ) {
+        self.cache
+            .borrow_mut()
+            .bind_buffer(BufferTarget::DispatchIndirectBuffer, buffer_name.to_u32());
+        self.count(true);
+        self.gl
+            .dispatch_compute_indirect_command(buffer_name, byte_offset, command);
+    }
+
+    #[inline]
+    pub unsafe fn delete_buffer(&self, name: BufferName) {
+        self.cache.borrow_mut().delete_buffer(name.to_u32());
+        self.gl.delete_buffer(name);
+    }
+
+    #[inline]
+    pub unsafe fn delete_texture(&self, name: TextureName) {
+        self.cache.borrow_mut().delete_texture(name.to_u32());
+        self.gl.delete_texture(name);
+    }
+
+    #[inline]
+    pub unsafe fn delete_sampler(&self, name: SamplerName) {
+        self.cache.borrow_mut().delete_sampler(name.to_u32());
+        self.gl.delete_sampler(name);
+    }
+
+    #[inline]
+    pub unsafe fn delete_vertex_array(&self, vertex_array_name: VertexArrayName) {
+        self.cache
+            .borrow_mut()
+            .delete_vertex_array(vertex_array_name.to_u32());
+        self.gl.delete_vertex_array(vertex_array_name);
+    }
+
+    #[inline]
+    pub unsafe fn delete_framebuffer(&self, name: NonDefaultFramebufferName) {
+        self.cache.borrow_mut().delete_framebuffer(name.to_u32());
+        self.gl.delete_framebuffer(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elides_repeated_binds() {
+        let mut cache = BindingCache::default();
+        assert!(cache.bind_buffer(BufferTarget::ArrayBuffer, 1));
+        assert!(!cache.bind_buffer(BufferTarget::ArrayBuffer, 1));
+        assert!(cache.bind_buffer(BufferTarget::UniformBuffer, 1));
+        assert!(cache.bind_buffer(BufferTarget::ArrayBuffer, 2));
+        assert!(update_unit(&mut cache.texture_units, 3, 7));
+        assert!(!update_unit(&mut cache.texture_units, 3, 7));
+        assert!(update_unit(&mut cache.texture_units, 0, 7));
+    }
+
+    #[test]
+    fn vertex_array_resets_element_array_buffer() {
+        let mut cache = BindingCache::default();
+        assert!(cache.bind_vertex_array(1));
+        assert!(cache.bind_buffer(BufferTarget::ElementArrayBuffer, 5));
+        assert!(!cache.bind_vertex_array(1));
+        assert!(!cache.bind_buffer(BufferTarget::ElementArrayBuffer, 5));
+        assert!(cache.bind_vertex_array(2));
+        assert!(cache.bind_buffer(BufferTarget::ElementArrayBuffer, 5));
+    }
+
+    #[test]
+    fn framebuffer_target_binds_draw_and_read() {
+        let mut cache = BindingCache::default();
+        assert!(cache.bind_framebuffer(FramebufferTarget::Framebuffer, 1));
+        assert!(!cache.bind_framebuffer(FramebufferTarget::DrawFramebuffer, 1));
+        assert!(!cache.bind_framebuffer(FramebufferTarget::ReadFramebuffer, 1));
+        assert!(cache.bind_framebuffer(FramebufferTarget::ReadFramebuffer, 2));
+        assert!(cache.bind_framebuffer(FramebufferTarget::Framebuffer, 1));
+    }
+
+    mod stubs {
+        use crate::gl::types::*;
+
+        pub extern "system" fn bind_2(_: GLuint, _: GLuint) {}
+        pub extern "system" fn bind_n(_: GLuint, _: GLsizei, _: *const GLuint) {}
+        pub extern "system" fn named_buffer_sub_data(
+            _: GLuint,
+            _: GLintptr,
+            _: GLsizeiptr,
+            _: *const GLvoid,
+        ) {
+        }
+        pub extern "system" fn multi_draw_arrays_indirect(
+            _: GLenum,
+            _: *const GLvoid,
+            _: GLsizei,
+            _: GLsizei,
+        ) {
+        }
+        pub extern "system" fn dispatch_compute_indirect(_: GLintptr) {}
+    }
+
+    /// A `Gl` whose binding calls do nothing so `CachedGl` can run without a
+    /// context. Any other call panics.
+    fn stub_gl() -> Gl {
+        unsafe {
+            Gl::load_with(|name| match name {
+                "glBindBuffer" | "glBindTexture" | "glBindTextureUnit" | "glBindSampler" => {
+                    stubs::bind_2 as *const c_void
+                }
+                "glBindTextures" | "glBindSamplers" => stubs::bind_n as *const c_void,
+                "glNamedBufferSubData" => stubs::named_buffer_sub_data as *const c_void,
+                "glMultiDrawArraysIndirect" => stubs::multi_draw_arrays_indirect as *const c_void,
+                "glDispatchComputeIndirect" => stubs::dispatch_compute_indirect as *const c_void,
+                _ => std::ptr::null(),
+            })
+        }
+    }
+
+    #[test]
+    fn bind_after_untracked_binding_change_is_issued() {
+        let gl = CachedGl::new(stub_gl());
+        let buffer = |name| BufferName::new(name).unwrap();
+        let texture = |name| TextureName::new(name).unwrap();
+        let sampler = |name| SamplerName::new(name).unwrap();
+        let assert_issued = |f: &dyn Fn()| {
+            gl.reset_stats();
+            f();
+            assert_eq!(
+                CachedGlStats {
+                    issued: 1,
+                    elided: 0
+                },
+                gl.stats()
+            );
+        };
+
+        unsafe {
+            gl.bind_buffer(BufferTarget::DrawIndirectBuffer, buffer(1));
+            gl.multi_draw_arrays_indirect_commands(
+                DrawMode::Triangles,
+                buffer(2),
+                0,
+                &[DrawArraysIndirectCommand::default()],
+            );
+            assert_issued(&|| gl.bind_buffer(BufferTarget::DrawIndirectBuffer, buffer(1)));
+
+            gl.bind_buffer(BufferTarget::DispatchIndirectBuffer, buffer(1));
+            gl.dispatch_compute_indirect_command(buffer(2), 0, &Default::default());
+            assert_issued(&|| gl.bind_buffer(BufferTarget::DispatchIndirectBuffer, buffer(1)));
+
+            gl.bind_texture_unit(0, texture(1));
+            gl.bind_textures(0, &[Some(texture(2))]);
+            assert_issued(&|| gl.bind_texture_unit(0, texture(1)));
+
+            gl.bind_texture_unit(1, texture(1));
+            gl.bind_texture(TextureTarget::Texture2D, texture(2));
+            assert_issued(&|| gl.bind_texture_unit(1, texture(1)));
+
+            gl.bind_sampler(0, sampler(1));
+            gl.bind_samplers(0, 1, &[sampler(2)]);
+            assert_issued(&|| gl.bind_sampler(0, sampler(1)));
+
+            gl.bind_buffer(BufferTarget::ArrayBuffer, buffer(1));
+            gl.gl().bind_buffer(BufferTarget::ArrayBuffer, buffer(2));
+            gl.invalidate();
+            assert_issued(&|| gl.bind_buffer(BufferTarget::ArrayBuffer, buffer(1)));
+
+            // Binds through the `CachedGl` are still elided.
+            gl.reset_stats();
+            gl.bind_buffer(BufferTarget::ArrayBuffer, buffer(1));
+            assert_eq!(1, gl.stats().elided);
+        }
+    }
+
+    #[test]
+    fn deleting_unbinds() {
+        let mut cache = BindingCache::default();
+        assert!(cache.bind_buffer(BufferTarget::ArrayBuffer, 1));
+        cache.delete_buffer(1);
+        // The name may be reused by a new buffer.
+        assert!(cache.bind_buffer(BufferTarget::ArrayBuffer, 1));
+    }
+}
@@ -41,4 +41,12 @@ macro_rules! impl_casts {
 impl_casts!(
     u32 => i32,
     i32 => u32,
+    i64 => u64,
 );
+
+impl CastFrom<i32> for bool {
+    #[inline]
+    fn cast_from(value: i32) -> Self {
+        value != 0
+    }
+}
@@ -0,0 +1,588 @@
+use crate::*;
+use std::fmt;
+
+/// Which attachment points a format can be attached to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RenderableKind {
+    Color,
+    Depth,
+    Stencil,
+    DepthStencil,
+}
+
+impl InternalFormat {
+    /// Returns `None` for formats that can not be rendered to. Color
+    /// renderability follows the "CR" column of the internal format tables in
+    /// the specification.
+    pub fn renderable_kind(self) -> Option<RenderableKind> {
+        use InternalFormat::*;
+        match self {
+            DepthComponent | DepthComponent16 | DepthComponent24 | DepthComponent32
+            | DepthComponent32f => Some(RenderableKind::Depth),
+            DepthStencil | Depth24Stencil8 | Depth32fStencil8 => Some(RenderableKind::DepthStencil),
+            StencilIndex1 | StencilIndex4 | StencilIndex8 | StencilIndex16 => {
+                Some(RenderableKind::Stencil)
+            }
+            R8Snorm | Rg8Snorm | Rgb8Snorm | Rgba8Snorm | R16Snorm | Rg16Snorm | Rgb16Snorm
+            | Rgba16Snorm | Srgb8 | Rgb9E5 => None,
+            CompressedRed
+            | CompressedRg
+            | CompressedRgb
+            | CompressedRgba
+            | CompressedSrgb
+            | CompressedSrgbAlpha
+            | CompressedRedRgtc1
+            | CompressedSignedRedRgtc1
+            | CompressedRgRgtc2
+            | CompressedSignedRgRgtc2
+            | CompressedRgbaBptcUnorm
+            | CompressedSrgbAlphaBptcUnorm
+            | CompressedRgbBptcSignedFloat
+            | CompressedRgbBptcUnsignedFloat
+            | COMPRESSED_RGB_S3TC_DXT1_EXT
+            | COMPRESSED_RGBA_S3TC_DXT1_EXT
+            | COMPRESSED_RGBA_S3TC_DXT3_EXT
+            | COMPRESSED_RGBA_S3TC_DXT5_EXT
+            | COMPRESSED_SRGB_S3TC_DXT1_EXT
+            | COMPRESSED_SRGB_ALPHA_S3TC_DXT1_EXT
+            | COMPRESSED_SRGB_ALPHA_S3TC_DXT3_EXT
+            | COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT => None,
+            _ => Some(RenderableKind::Color),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FramebufferLayer {
+    /// Attach the level of a texture without layers.
+    NotLayered,
+    /// Attach a single layer of an array, cube map or 3D texture.
+    Layer(u32),
+    /// Attach every layer of an array, cube map or 3D texture. Either all or
+    /// none of the attachments must be layered.
+    Layered,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FramebufferImageSource {
+    Texture {
+        name: TextureName,
+        level: u32,
+        layer: FramebufferLayer,
+    },
+    Renderbuffer(RenderbufferName),
+}
+
+/// An image to attach together with the properties of its storage that
+/// `FramebufferDesc` validates before creating the framebuffer.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FramebufferImage {
+    pub source: FramebufferImageSource,
+    pub format: InternalFormat,
+    /// 0 for images that are not multisampled.
+    pub samples: u32,
+}
+
+impl FramebufferImage {
+    pub fn texture(name: TextureName, format: InternalFormat) -> Self {
+        FramebufferImage {
+            source: FramebufferImageSource::Texture {
+                name,
+                level: 0,
+                layer: FramebufferLayer::NotLayered,
+            },
+            format,
+            samples: 0,
+        }
+    }
+
+    pub fn renderbuffer(name: RenderbufferName, format: InternalFormat) -> Self {
+        FramebufferImage {
+            source: FramebufferImageSource::Renderbuffer(name),
+            format,
+            samples: 0,
+        }
+    }
+
+    /// Panics for renderbuffers.
+    pub fn level(mut self, level: u32) -> Self {
+        match &mut self.source {
+            FramebufferImageSource::Texture { level: l, .. } => *l = level,
+            FramebufferImageSource::Renderbuffer(_) => panic!("Renderbuffers have no levels."),
+        }
+        self
+    }
+
+    /// Panics for renderbuffers.
+    pub fn layer(mut self, layer: FramebufferLayer) -> Self {
+        match &mut self.source {
+            FramebufferImageSource::Texture { layer: l, .. } => *l = layer,
+            FramebufferImageSource::Renderbuffer(_) => panic!("Renderbuffers have no layers."),
+        }
+        self
+    }
+
+    pub fn samples(mut self, samples: u32) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    fn is_layered(&self) -> bool {
+        match self.source {
+            FramebufferImageSource::Texture { layer, .. } => layer == FramebufferLayer::Layered,
+            FramebufferImageSource::Renderbuffer(_) => false,
+        }
+    }
+}
+
+fn attachment_name(attachment: FramebufferAttachment) -> String {
+    let value = attachment.to_u32();
+    match value {
+        gl::NONE => "NONE".to_string(),
+        gl::DEPTH_ATTACHMENT => "DEPTH_ATTACHMENT".to_string(),
+        gl::STENCIL_ATTACHMENT => "STENCIL_ATTACHMENT".to_string(),
+        gl::DEPTH_STENCIL_ATTACHMENT => "DEPTH_STENCIL_ATTACHMENT".to_string(),
+        _ => format!(
+            "COLOR_ATTACHMENT{}",
+            value.wrapping_sub(gl::COLOR_ATTACHMENT0)
+        ),
+    }
+}
+
+fn color_attachment_index(attachment: FramebufferAttachment) -> Option<u32> {
+    // COLOR_ATTACHMENT0 through COLOR_ATTACHMENT31 are consecutive.
+    let index = attachment.to_u32().wrapping_sub(gl::COLOR_ATTACHMENT0);
+    if index < 32 {
+        Some(index)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FramebufferError {
+    NoAttachments,
+    /// Not one of the `COLOR_ATTACHMENTi` attachment points.
+    NotColorAttachment(FramebufferAttachment),
+    TooManyColorAttachments {
+        attachment: FramebufferAttachment,
+        max: MaxColorAttachments,
+    },
+    DuplicateAttachment(FramebufferAttachment),
+    /// A depth stencil image conflicts with a separate depth or stencil image.
+    ConflictingDepthStencil,
+    NotRenderable {
+        attachment: FramebufferAttachment,
+        format: InternalFormat,
+    },
+    SampleCountMismatch {
+        attachment: FramebufferAttachment,
+        samples: u32,
+        expected: u32,
+    },
+    LayeredMismatch(FramebufferAttachment),
+    /// The configuration passed validation but the driver rejected it.
+    Incomplete(FramebufferStatus),
+}
+
+impl fmt::Display for FramebufferError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FramebufferError::NoAttachments => write!(f, "The framebuffer has no attachments."),
+            FramebufferError::NotColorAttachment(attachment) => write!(
+                f,
+                "{} is not a color attachment.",
+                attachment_name(attachment)
+            ),
+            FramebufferError::TooManyColorAttachments { attachment, max } => write!(
+                f,
+                "{} exceeds the maximum of {} color attachments.",
+                attachment_name(attachment),
+                max.to_u32()
+            ),
+            FramebufferError::DuplicateAttachment(attachment) => write!(
+                f,
+                "{} is attached more than once.",
+                attachment_name(attachment)
+            ),
+            FramebufferError::ConflictingDepthStencil => write!(
+                f,
+                "DEPTH_STENCIL_ATTACHMENT can not be combined with DEPTH_ATTACHMENT or STENCIL_ATTACHMENT."
+            ),
+            FramebufferError::NotRenderable { attachment, format } => write!(
+                f,
+                "The internal format {:?} of {} is not renderable at that attachment point.",
+                format,
+                attachment_name(attachment)
+            ),
+            FramebufferError::SampleCountMismatch {
+                attachment,
+                samples,
+                expected,
+            } => write!(
+                f,
+                "{} has {} samples but the other attachments have {}.",
+                attachment_name(attachment),
+                samples,
+                expected
+            ),
+            FramebufferError::LayeredMismatch(attachment) => write!(
+                f,
+                "{} is layered differently from the other attachments.",
+                attachment_name(attachment)
+            ),
+            FramebufferError::Incomplete(status) => f.write_str(match status {
+                FramebufferStatus::FramebufferComplete => "The framebuffer is complete.",
+                FramebufferStatus::FramebufferUndefined => "The default framebuffer does not exist.",
+                FramebufferStatus::FramebufferIncompleteAttachment => {
+                    "An attachment is incomplete, for example because its image has a size of zero or its texture level is not defined."
+                }
+                FramebufferStatus::FramebufferIncompleteMissingAttachment => {
+                    "The framebuffer has no attachments and no default width and height."
+                }
+                FramebufferStatus::FramebufferIncompleteDrawBuffer => {
+                    "A draw buffer names an attachment point without an image."
+                }
+                FramebufferStatus::FramebufferIncompleteReadBuffer => {
+                    "The read buffer names an attachment point without an image."
+                }
+                FramebufferStatus::FramebufferUnsupported => {
+                    "The implementation does not support this combination of internal formats."
+                }
+                FramebufferStatus::FramebufferIncompleteMultisample => {
+                    "The attachments do not have the same number of samples or fixed sample locations."
+                }
+                FramebufferStatus::FramebufferIncompleteLayerTargets => {
+                    "The attachments are not all layered or are layered with different targets."
+                }
+            }),
+        }
+    }
+}
+
+impl std::error::Error for FramebufferError {}
+
+/// Describes the attachments of a framebuffer so that it can be validated
+/// before any OpenGL calls are made.
+#[derive(Debug, Clone, Default)]
+pub struct FramebufferDesc {
+    /// Draw buffer `i` writes to the `i`th color attachment.
+    pub color: Vec<(FramebufferAttachment, FramebufferImage)>,
+    pub depth: Option<FramebufferImage>,
+    pub stencil: Option<FramebufferImage>,
+    pub depth_stencil: Option<FramebufferImage>,
+}
+
+impl FramebufferDesc {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn color<A: Into<FramebufferAttachment>>(
+        mut self,
+        attachment: A,
+        image: FramebufferImage,
+    ) -> Self {
+        self.color.push((attachment.into(), image));
+        self
+    }
+
+    pub fn depth(mut self, image: FramebufferImage) -> Self {
+        self.depth = Some(image);
+        self
+    }
+
+    pub fn stencil(mut self, image: FramebufferImage) -> Self {
+        self.stencil = Some(image);
+        self
+    }
+
+    pub fn depth_stencil(mut self, image: FramebufferImage) -> Self {
+        self.depth_stencil = Some(image);
+        self
+    }
+
+    fn attachments(&self) -> impl Iterator<Item = (FramebufferAttachment, &FramebufferImage)> {
+        self.color
+            .iter()
+            .map(|(attachment, image)| (*attachment, image))
+            .chain(
+                self.depth
+                    .iter()
+                    .map(|image| (FramebufferAttachment::from(DEPTH_ATTACHMENT), image)),
+            )
+            .chain(
+                self.stencil
+                    .iter()
+                    .map(|image| (FramebufferAttachment::from(STENCIL_ATTACHMENT), image)),
+            )
+            .chain(
+                self.depth_stencil
+                    .iter()
+                    .map(|image| (FramebufferAttachment::from(DEPTH_STENCIL_ATTACHMENT), image)),
+            )
+    }
+
+    /// Checks the rules of framebuffer completeness that do not depend on
+    /// the state of the attached objects.
+    pub fn validate(&self, max: MaxColorAttachments) -> Result<(), FramebufferError> {
+        if self.depth_stencil.is_some() && (self.depth.is_some() || self.stencil.is_some()) {
+            return Err(FramebufferError::ConflictingDepthStencil);
+        }
+
+        for (i, &(attachment, _)) in self.color.iter().enumerate() {
+            let index = color_attachment_index(attachment)
+                .ok_or(FramebufferError::NotColorAttachment(attachment))?;
+            if index >= max.to_u32() {
+                return Err(FramebufferError::TooManyColorAttachments { attachment, max });
+            }
+            if self.color[..i].iter().any(|&(a, _)| a == attachment) {
+                return Err(FramebufferError::DuplicateAttachment(attachment));
+            }
+        }
+
+        let (_, first) = self
+            .attachments()
+            .next()
+            .ok_or(FramebufferError::NoAttachments)?;
+
+        for (attachment, image) in self.attachments() {
+            let kind = image.format.renderable_kind();
+            let renderable = match attachment.to_u32() {
+                gl::DEPTH_ATTACHMENT => matches!(
+                    kind,
+                    Some(RenderableKind::Depth) | Some(RenderableKind::DepthStencil)
+                ),
+                gl::STENCIL_ATTACHMENT => matches!(
+                    kind,
+                    Some(RenderableKind::Stencil) | Some(RenderableKind::DepthStencil)
+                ),
+                gl::DEPTH_STENCIL_ATTACHMENT => kind == Some(RenderableKind::DepthStencil),
+                _ => kind == Some(RenderableKind::Color),
+            };
+            if !renderable {
+                return Err(FramebufferError::NotRenderable {
+                    attachment,
+                    format: image.format,
+                });
+            }
+            if image.samples != first.samples {
+                return Err(FramebufferError::SampleCountMismatch {
+                    attachment,
+                    samples: image.samples,
+                    expected: first.samples,
+                });
+            }
+            if image.is_layered() != first.is_layered() {
+                return Err(FramebufferError::LayeredMismatch(attachment));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Gl {
+    unsafe fn attach_framebuffer_image(
+        &self,
+        framebuffer_name: NonDefaultFramebufferName,
+        attachment: FramebufferAttachment,
+        image: &FramebufferImage,
+    ) {
+        match image.source {
+            FramebufferImageSource::Texture {
+                name,
+                level,
+                layer: FramebufferLayer::Layer(layer),
+            } => self.named_framebuffer_texture_layer(
+                framebuffer_name,
+                attachment,
+                name,
+                level as i32,
+                layer as i32,
+            ),
+            FramebufferImageSource::Texture { name, level, .. } => {
+                self.named_framebuffer_texture(framebuffer_name, attachment, name, level as i32)
+            }
+            FramebufferImageSource::Renderbuffer(name) => self.named_framebuffer_renderbuffer(
+                framebuffer_name,
+                attachment,
+                RenderbufferTarget::Renderbuffer,
+                name,
+            ),
+        }
+    }
+
+    /// Validates `desc`, creates a framebuffer with its attachments and
+    /// checks its completeness. The framebuffer is deleted when it is
+    /// incomplete.
+    pub unsafe fn create_framebuffer_from_desc(
+        &self,
+        desc: &FramebufferDesc,
+    ) -> Result<NonDefaultFramebufferName, FramebufferError> {
+        desc.validate(self.get_max_color_attachments())?;
+
+        let framebuffer_name = self.create_framebuffer();
+        for (attachment, image) in desc.attachments() {
+            self.attach_framebuffer_image(framebuffer_name, attachment, image);
+        }
+        let draw_buffers: Vec<FramebufferAttachment> = if desc.color.is_empty() {
+            vec![NONE.into()]
+        } else {
+            desc.color
+                .iter()
+                .map(|&(attachment, _)| attachment)
+                .collect()
+        };
+        self.named_framebuffer_draw_buffers(framebuffer_name, &draw_buffers);
+
+        match self
+            .check_named_framebuffer_status(framebuffer_name, FramebufferTarget::DrawFramebuffer)
+        {
+            FramebufferStatus::FramebufferComplete => Ok(framebuffer_name),
+            status => {
+                self.delete_framebuffer(framebuffer_name);
+                Err(FramebufferError::Incomplete(status))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX: MaxColorAttachments = MaxColorAttachments(8);
+
+    fn texture(format: InternalFormat) -> FramebufferImage {
+        FramebufferImage::texture(TextureName::new(1).unwrap(), format)
+    }
+
+    fn renderbuffer(format: InternalFormat) -> FramebufferImage {
+        FramebufferImage::renderbuffer(RenderbufferName::new(2).unwrap(), format)
+    }
+
+    fn color(index: u32) -> FramebufferAttachment {
+        FramebufferAttachment::new(index, MaxColorAttachments(32)).unwrap()
+    }
+
+    #[test]
+    fn accepts_valid_descriptions() {
+        let desc = FramebufferDesc::new()
+            .color(COLOR_ATTACHMENT0, texture(InternalFormat::Rgba16f))
+            .color(COLOR_ATTACHMENT1, texture(InternalFormat::Rgb10A2).level(2))
+            .depth_stencil(renderbuffer(InternalFormat::Depth24Stencil8));
+        assert_eq!(Ok(()), desc.validate(MAX));
+
+        let desc = FramebufferDesc::new()
+            .depth(texture(InternalFormat::DepthComponent32f).layer(FramebufferLayer::Layer(3)));
+        assert_eq!(Ok(()), desc.validate(MAX));
+
+        let desc = FramebufferDesc::new()
+            .color(
+                COLOR_ATTACHMENT0,
+                texture(InternalFormat::Rgba8).layer(FramebufferLayer::Layered),
+            )
+            .depth(texture(InternalFormat::DepthComponent24).layer(FramebufferLayer::Layered));
+        assert_eq!(Ok(()), desc.validate(MAX));
+    }
+
+    #[test]
+    fn rejects_invalid_attachment_points() {
+        assert_eq!(
+            Err(FramebufferError::NoAttachments),
+            FramebufferDesc::new().validate(MAX)
+        );
+        assert_eq!(
+            Err(FramebufferError::TooManyColorAttachments {
+                attachment: color(8),
+                max: MAX,
+            }),
+            FramebufferDesc::new()
+                .color(color(8), texture(InternalFormat::Rgba8))
+                .validate(MAX)
+        );
+        assert_eq!(
+            Err(FramebufferError::NotColorAttachment(
+                DEPTH_ATTACHMENT.into()
+            )),
+            FramebufferDesc::new()
+                .color(DEPTH_ATTACHMENT, texture(InternalFormat::DepthComponent24))
+                .validate(MAX)
+        );
+        assert_eq!(
+            Err(FramebufferError::DuplicateAttachment(color(1))),
+            FramebufferDesc::new()
+                .color(color(1), texture(InternalFormat::Rgba8))
+                .color(color(1), texture(InternalFormat::Rgba8))
+                .validate(MAX)
+        );
+        assert_eq!(
+            Err(FramebufferError::ConflictingDepthStencil),
+            FramebufferDesc::new()
+                .depth(texture(InternalFormat::DepthComponent24))
+                .depth_stencil(texture(InternalFormat::Depth24Stencil8))
+                .validate(MAX)
+        );
+    }
+
+    #[test]
+    fn rejects_mismatched_images() {
+        assert_eq!(
+            Err(FramebufferError::NotRenderable {
+                attachment: color(0),
+                format: InternalFormat::Rgba8Snorm,
+            }),
+            FramebufferDesc::new()
+                .color(color(0), texture(InternalFormat::Rgba8Snorm))
+                .validate(MAX)
+        );
+        assert_eq!(
+            Err(FramebufferError::NotRenderable {
+                attachment: DEPTH_ATTACHMENT.into(),
+                format: InternalFormat::Rgba8,
+            }),
+            FramebufferDesc::new()
+                .depth(texture(InternalFormat::Rgba8))
+                .validate(MAX)
+        );
+        assert_eq!(
+            Err(FramebufferError::SampleCountMismatch {
+                attachment: DEPTH_ATTACHMENT.into(),
+                samples: 0,
+                expected: 4,
+            }),
+            FramebufferDesc::new()
+                .color(color(0), renderbuffer(InternalFormat::Rgba8).samples(4))
+                .depth(renderbuffer(InternalFormat::DepthComponent24))
+                .validate(MAX)
+        );
+        assert_eq!(
+            Err(FramebufferError::LayeredMismatch(color(1))),
+            FramebufferDesc::new()
+                .color(
+                    color(0),
+                    texture(InternalFormat::Rgba8).layer(FramebufferLayer::Layered)
+                )
+                .color(color(1), texture(InternalFormat::Rgba8))
+                .validate(MAX)
+        );
+    }
+
+    #[test]
+    fn describes_errors() {
+        assert_eq!(
+            "COLOR_ATTACHMENT8 exceeds the maximum of 8 color attachments.",
+            FramebufferError::TooManyColorAttachments {
+                attachment: color(8),
+                max: MAX,
+            }
+            .to_string()
+        );
+        assert_eq!(
+            "The attachments do not have the same number of samples or fixed sample locations.",
+            FramebufferError::Incomplete(FramebufferStatus::FramebufferIncompleteMultisample)
+                .to_string()
+        );
+    }
+}
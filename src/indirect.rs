@@ -0,0 +1,163 @@
+use crate::*;
+
+/// The layout of a single command read by `draw_arrays_indirect` and
+/// `multi_draw_arrays_indirect`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct DrawArraysIndirectCommand {
+    pub count: u32,
+    pub instance_count: u32,
+    pub first: u32,
+    pub base_instance: u32,
+}
+
+/// The layout of a single command read by `draw_elements_indirect` and
+/// `multi_draw_elements_indirect`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct DrawElementsIndirectCommand {
+    pub count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub base_vertex: i32,
+    pub base_instance: u32,
+}
+
+/// The layout of the command read by `dispatch_compute_indirect`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct DispatchIndirectCommand {
+    pub num_groups_x: u32,
+    pub num_groups_y: u32,
+    pub num_groups_z: u32,
+}
+
+/// Only used with the command structs above which have no padding.
+#[inline]
+fn commands_as_bytes<T: Copy>(commands: &[T]) -> &[u8] {
+    unsafe {
+        std::slice::from_raw_parts(
+            commands.as_ptr() as *const u8,
+            std::mem::size_of_val(commands),
+        )
+    }
+}
+
+impl Gl {
+    /// Writes `commands` to `buffer_name` at `byte_offset`, binds it to
+    /// `DRAW_INDIRECT_BUFFER` and draws all of them.
+    #[inline]
+    pub unsafe fn multi_draw_arrays_indirect_commands<M>(
+        &self,
+        mode: M,
+        buffer_name: BufferName,
+        byte_offset: usize,
+        commands: &[DrawArraysIndirectCommand],
+    ) where
+        M: Into<DrawMode>,
+    {
+        debug_assert_eq!(0, byte_offset % 4);
+        self.named_buffer_sub_data(buffer_name, byte_offset, commands_as_bytes(commands));
+        self.bind_buffer(BufferTarget::DrawIndirectBuffer, buffer_name);
+        self.multi_draw_arrays_indirect(
+            mode,
+            byte_offset,
+            commands.len() as i32,
+            std::mem::size_of::<DrawArraysIndirectCommand>() as i32,
+        );
+    }
+
+    /// Writes `commands` to `buffer_name` at `byte_offset`, binds it to
+    /// `DRAW_INDIRECT_BUFFER` and draws all of them.
+    #[inline]
+    pub unsafe fn multi_draw_elements_indirect_commands<M, T>(
+        &self,
+        mode: M,
+        ty: T,
+        buffer_name: BufferName,
+        byte_offset: usize,
+        commands: &[DrawElementsIndirectCommand],
+    ) where
+        M: Into<DrawMode>,
+        T: Into<DrawElementsType>,
+    {
+        debug_assert_eq!(0, byte_offset % 4);
+        self.named_buffer_sub_data(buffer_name, byte_offset, commands_as_bytes(commands));
+        self.bind_buffer(BufferTarget::DrawIndirectBuffer, buffer_name);
+        self.multi_draw_elements_indirect(
+            mode,
+            ty,
+            byte_offset,
+            commands.len() as i32,
+            std::mem::size_of::<DrawElementsIndirectCommand>() as i32,
+        );
+    }
+
+    /// Writes `command` to `buffer_name` at `byte_offset`, binds it to
+    /// `DISPATCH_INDIRECT_BUFFER` and dispatches it.
+    #[inline]
+    pub unsafe fn dispatch_compute_indirect_command(
+        &self,
+        buffer_name: BufferName,
+        byte_offset: usize,
+        command: &DispatchIndirectCommand,
+    ) {
+        debug_assert_eq!(0, byte_offset % 4);
+        self.named_buffer_sub_data(
+            buffer_name,
+            byte_offset,
+            commands_as_bytes(std::slice::from_ref(command)),
+        );
+        self.bind_buffer(BufferTarget::DispatchIndirectBuffer, buffer_name);
+        self.dispatch_compute_indirect(byte_offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem;
+
+    fn offset_of<T, F>(base: &T, field: &F) -> usize {
+        field as *const F as usize - base as *const T as usize
+    }
+
+    #[test]
+    fn draw_arrays_indirect_command_layout() {
+        let c = DrawArraysIndirectCommand::default();
+        assert_eq!(16, mem::size_of::<DrawArraysIndirectCommand>());
+        assert_eq!(4, mem::align_of::<DrawArraysIndirectCommand>());
+        assert_eq!(0, offset_of(&c, &c.count));
+        assert_eq!(4, offset_of(&c, &c.instance_count));
+        assert_eq!(8, offset_of(&c, &c.first));
+        assert_eq!(12, offset_of(&c, &c.base_instance));
+    }
+
+    #[test]
+    fn draw_elements_indirect_command_layout() {
+        let c = DrawElementsIndirectCommand::default();
+        assert_eq!(20, mem::size_of::<DrawElementsIndirectCommand>());
+        assert_eq!(4, mem::align_of::<DrawElementsIndirectCommand>());
+        assert_eq!(0, offset_of(&c, &c.count));
+        assert_eq!(4, offset_of(&c, &c.instance_count));
+        assert_eq!(8, offset_of(&c, &c.first_index));
+        assert_eq!(12, offset_of(&c, &c.base_vertex));
+        assert_eq!(16, offset_of(&c, &c.base_instance));
+    }
+
+    #[test]
+    fn dispatch_indirect_command_layout() {
+        let c = DispatchIndirectCommand::default();
+        assert_eq!(12, mem::size_of::<DispatchIndirectCommand>());
+        assert_eq!(4, mem::align_of::<DispatchIndirectCommand>());
+        assert_eq!(0, offset_of(&c, &c.num_groups_x));
+        assert_eq!(4, offset_of(&c, &c.num_groups_y));
+        assert_eq!(8, offset_of(&c, &c.num_groups_z));
+    }
+
+    #[test]
+    fn commands_as_bytes_covers_slice() {
+        let commands = [DrawArraysIndirectCommand::default(); 3];
+        assert_eq!(48, commands_as_bytes(&commands).len());
+    }
+}
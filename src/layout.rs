@@ -0,0 +1,543 @@
+use crate::*;
+
+/// The rules used to lay out the members of a uniform or shader storage block.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum LayoutStandard {
+    /// Available for uniform and shader storage blocks. Rounds the alignment
+    /// of arrays and structures up to that of a `vec4`.
+    Std140,
+    /// Only available for shader storage blocks.
+    Std430,
+}
+
+/// The size and base alignment of a type inside a block, in bytes.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TypeLayout {
+    pub size: u32,
+    pub align: u32,
+}
+
+/// The placement of a single member of a structure.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MemberLayout {
+    pub name: &'static str,
+    pub offset: u32,
+    pub layout: TypeLayout,
+}
+
+impl MemberLayout {
+    /// The offset of the first byte after this member.
+    #[inline]
+    pub const fn end(&self) -> u32 {
+        self.offset + self.layout.size
+    }
+}
+
+// `div_ceil` and `next_multiple_of` need a newer compiler than this crate
+// supports.
+#[allow(clippy::manual_div_ceil)]
+#[inline]
+const fn round_up(value: u32, align: u32) -> u32 {
+    (value + align - 1) / align * align
+}
+
+#[inline]
+const fn max(a: u32, b: u32) -> u32 {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+/// The layout of a variable as reported by GL for the members of a block.
+/// Arrays of structures are expanded into their elements, arrays of other
+/// types are reported once as `name[0]` with an array stride.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct BlockVariableLayout {
+    pub name: String,
+    pub offset: u32,
+    /// Zero if the variable is not an array.
+    pub array_stride: u32,
+    /// Zero if the variable is not a matrix.
+    pub matrix_stride: u32,
+    pub is_row_major: bool,
+}
+
+impl LayoutStandard {
+    /// A scalar of `size` bytes. A GLSL `bool` occupies 4 bytes.
+    #[inline]
+    pub const fn scalar(self, size: u32) -> TypeLayout {
+        TypeLayout { size, align: size }
+    }
+
+    /// A vector of 1 to 4 components. A vector of 3 components is aligned
+    /// like one of 4.
+    #[inline]
+    pub const fn vector(self, scalar: TypeLayout, components: u32) -> TypeLayout {
+        TypeLayout {
+            size: scalar.size * components,
+            align: scalar.align * if components == 3 { 4 } else { components },
+        }
+    }
+
+    /// The base alignment of an array with elements of the given layout.
+    #[inline]
+    pub const fn array_align(self, element: TypeLayout) -> u32 {
+        match self {
+            LayoutStandard::Std140 => round_up(element.align, 16),
+            LayoutStandard::Std430 => element.align,
+        }
+    }
+
+    /// The distance between consecutive elements of an array.
+    #[inline]
+    pub const fn array_stride(self, element: TypeLayout) -> u32 {
+        round_up(element.size, self.array_align(element))
+    }
+
+    #[inline]
+    pub const fn array(self, element: TypeLayout, len: u32) -> TypeLayout {
+        TypeLayout {
+            size: self.array_stride(element) * len,
+            align: self.array_align(element),
+        }
+    }
+
+    /// A matrix is laid out as an array of its columns, or of its rows when
+    /// it is declared `row_major`.
+    #[inline]
+    pub const fn matrix(self, vector: TypeLayout, vectors: u32) -> TypeLayout {
+        self.array(vector, vectors)
+    }
+
+    /// The offset of `members[index]` when the members are placed in order.
+    pub const fn member_offset(self, members: &[TypeLayout], index: usize) -> u32 {
+        let mut offset = 0;
+        let mut i = 0;
+        while i < index {
+            offset = round_up(offset, members[i].align) + members[i].size;
+            i += 1;
+        }
+        round_up(offset, members[index].align)
+    }
+
+    pub const fn structure(self, members: &[TypeLayout]) -> TypeLayout {
+        let mut align = match self {
+            LayoutStandard::Std140 => 16,
+            LayoutStandard::Std430 => 1,
+        };
+        let mut end = 0;
+        let mut i = 0;
+        while i < members.len() {
+            align = max(align, members[i].align);
+            end = round_up(end, members[i].align) + members[i].size;
+            i += 1;
+        }
+        TypeLayout {
+            size: round_up(end, align),
+            align,
+        }
+    }
+
+    /// Places every member of a structure.
+    pub const fn members<const N: usize>(
+        self,
+        names: [&'static str; N],
+        layouts: [TypeLayout; N],
+    ) -> [MemberLayout; N] {
+        let mut members = [MemberLayout {
+            name: "",
+            offset: 0,
+            layout: TypeLayout { size: 0, align: 1 },
+        }; N];
+        let mut i = 0;
+        while i < N {
+            members[i] = MemberLayout {
+                name: names[i],
+                offset: self.member_offset(&layouts, i),
+                layout: layouts[i],
+            };
+            i += 1;
+        }
+        members
+    }
+
+    #[inline]
+    pub const fn layout_of<T: BlockMember>(self) -> TypeLayout {
+        match self {
+            LayoutStandard::Std140 => T::STD140,
+            LayoutStandard::Std430 => T::STD430,
+        }
+    }
+}
+
+/// A type with a GLSL counterpart that can be a member of a uniform or shader
+/// storage block.
+pub trait BlockMember {
+    const STD140: TypeLayout;
+    const STD430: TypeLayout;
+
+    /// Whether GL reports the members of this type instead of the type
+    /// itself. True for arrays and structures.
+    const AGGREGATE: bool = false;
+
+    /// Appends the variables GL reports for a member of this type named
+    /// `name` at `offset`.
+    fn block_variables(
+        standard: LayoutStandard,
+        name: &str,
+        offset: u32,
+        variables: &mut Vec<BlockVariableLayout>,
+    ) {
+        let _ = standard;
+        variables.push(BlockVariableLayout {
+            name: String::from(name),
+            offset,
+            array_stride: 0,
+            matrix_stride: 0,
+            is_row_major: false,
+        });
+    }
+}
+
+/// Marks types that can be the element of a block array. Scalars are not
+/// included because `[f32; 3]` is a `vec3`. Use `[f32; 1]` as the element
+/// type for a `float[N]`.
+pub trait BlockArrayElement: BlockMember {}
+
+/// Converts a value into a padded representation that can be copied into a
+/// std140 block as is. Implemented for structs through `#[derive(Std140)]`
+/// which requires the `derive` feature.
+pub trait AsStd140: BlockMember {
+    /// Has exactly `STD140.size` bytes.
+    type Std140: Copy;
+
+    /// Has exactly as many bytes as the std140 array stride of `Self`.
+    type Std140Element: Copy;
+
+    fn as_std140(&self) -> Self::Std140;
+
+    fn as_std140_element(&self) -> Self::Std140Element;
+}
+
+/// The member placement of a struct. Implemented through `#[derive(Std140)]`.
+pub trait BlockStruct: BlockMember {
+    const STD140_MEMBERS: &'static [MemberLayout];
+    const STD430_MEMBERS: &'static [MemberLayout];
+}
+
+impl BlockMember for bool {
+    const STD140: TypeLayout = LayoutStandard::Std140.scalar(4);
+    const STD430: TypeLayout = LayoutStandard::Std430.scalar(4);
+}
+
+impl AsStd140 for bool {
+    type Std140 = u32;
+    type Std140Element = [u32; 4];
+
+    #[inline]
+    fn as_std140(&self) -> u32 {
+        *self as u32
+    }
+
+    #[inline]
+    fn as_std140_element(&self) -> [u32; 4] {
+        [*self as u32, 0, 0, 0]
+    }
+}
+
+macro_rules! impl_scalars {
+    ($($S:ty => $element:expr,)*) => {
+        $(
+            impl BlockMember for $S {
+                const STD140: TypeLayout =
+                    LayoutStandard::Std140.scalar(std::mem::size_of::<$S>() as u32);
+                const STD430: TypeLayout =
+                    LayoutStandard::Std430.scalar(std::mem::size_of::<$S>() as u32);
+            }
+
+            impl AsStd140 for $S {
+                type Std140 = $S;
+                type Std140Element = [$S; $element];
+
+                #[inline]
+                fn as_std140(&self) -> $S {
+                    *self
+                }
+
+                #[inline]
+                fn as_std140_element(&self) -> Self::Std140Element {
+                    let mut element = [<$S>::default(); $element];
+                    element[0] = *self;
+                    element
+                }
+            }
+        )*
+    };
+}
+
+impl_scalars! {
+    f32 => 4,
+    f64 => 2,
+    i32 => 4,
+    u32 => 4,
+}
+
+macro_rules! impl_vectors {
+    ($($S:ty { $($n:expr => $element:expr,)* })*) => {
+        $(
+            $(
+                impl BlockMember for [$S; $n] {
+                    const STD140: TypeLayout =
+                        LayoutStandard::Std140.vector(<$S as BlockMember>::STD140, $n);
+                    const STD430: TypeLayout =
+                        LayoutStandard::Std430.vector(<$S as BlockMember>::STD430, $n);
+                }
+
+                impl BlockArrayElement for [$S; $n] {}
+
+                impl AsStd140 for [$S; $n] {
+                    type Std140 = [$S; $n];
+                    type Std140Element = [$S; $element];
+
+                    #[inline]
+                    fn as_std140(&self) -> Self::Std140 {
+                        *self
+                    }
+
+                    #[inline]
+                    fn as_std140_element(&self) -> Self::Std140Element {
+                        let mut element = [<$S>::default(); $element];
+                        element[..$n].copy_from_slice(self);
+                        element
+                    }
+                }
+            )*
+        )*
+    };
+}
+
+impl_vectors! {
+    f32 { 1 => 4, 2 => 4, 3 => 4, 4 => 4, }
+    f64 { 1 => 2, 2 => 2, 3 => 4, 4 => 4, }
+    i32 { 1 => 4, 2 => 4, 3 => 4, 4 => 4, }
+    u32 { 1 => 4, 2 => 4, 3 => 4, 4 => 4, }
+}
+
+impl<T: BlockArrayElement, const N: usize> BlockMember for [T; N] {
+    const STD140: TypeLayout = LayoutStandard::Std140.array(T::STD140, N as u32);
+    const STD430: TypeLayout = LayoutStandard::Std430.array(T::STD430, N as u32);
+
+    const AGGREGATE: bool = true;
+
+    fn block_variables(
+        standard: LayoutStandard,
+        name: &str,
+        offset: u32,
+        variables: &mut Vec<BlockVariableLayout>,
+    ) {
+        let stride = standard.array_stride(standard.layout_of::<T>());
+        if T::AGGREGATE {
+            for i in 0..N {
+                let name = format!("{}[{}]", name, i);
+                T::block_variables(standard, &name, offset + stride * i as u32, variables);
+            }
+        } else {
+            let first = variables.len();
+            T::block_variables(standard, &format!("{}[0]", name), offset, variables);
+            variables[first].array_stride = stride;
+        }
+    }
+}
+
+impl<T: BlockArrayElement, const N: usize> BlockArrayElement for [T; N] {}
+
+impl<T: BlockArrayElement + AsStd140, const N: usize> AsStd140 for [T; N] {
+    type Std140 = [T::Std140Element; N];
+    type Std140Element = [T::Std140Element; N];
+
+    #[inline]
+    fn as_std140(&self) -> Self::Std140 {
+        std::array::from_fn(|i| self[i].as_std140_element())
+    }
+
+    #[inline]
+    fn as_std140_element(&self) -> Self::Std140Element {
+        self.as_std140()
+    }
+}
+
+macro_rules! impl_matrices {
+    ($($Matrix:ident($row_major:expr) { $([[f32; $n:expr]; $m:expr],)* })*) => {
+        $(
+            $(
+                impl BlockMember for $Matrix<[[f32; $n]; $m]> {
+                    const STD140: TypeLayout =
+                        LayoutStandard::Std140.matrix(<[f32; $n] as BlockMember>::STD140, $m);
+                    const STD430: TypeLayout =
+                        LayoutStandard::Std430.matrix(<[f32; $n] as BlockMember>::STD430, $m);
+
+                    fn block_variables(
+                        standard: LayoutStandard,
+                        name: &str,
+                        offset: u32,
+                        variables: &mut Vec<BlockVariableLayout>,
+                    ) {
+                        variables.push(BlockVariableLayout {
+                            name: String::from(name),
+                            offset,
+                            array_stride: 0,
+                            matrix_stride: standard
+                                .array_stride(standard.layout_of::<[f32; $n]>()),
+                            is_row_major: $row_major,
+                        });
+                    }
+                }
+
+                impl BlockArrayElement for $Matrix<[[f32; $n]; $m]> {}
+
+                impl AsStd140 for $Matrix<[[f32; $n]; $m]> {
+                    type Std140 = [[f32; 4]; $m];
+                    type Std140Element = [[f32; 4]; $m];
+
+                    #[inline]
+                    fn as_std140(&self) -> Self::Std140 {
+                        self.0.as_std140()
+                    }
+
+                    #[inline]
+                    fn as_std140_element(&self) -> Self::Std140Element {
+                        self.as_std140()
+                    }
+                }
+            )*
+        )*
+    };
+}
+
+// The matrices in `matrix.rs`. A `RowMajorMatrix` must be declared
+// `layout(row_major)` in the block.
+impl_matrices! {
+    ColMajorMatrix(false) {
+        [[f32; 2]; 2],
+        [[f32; 3]; 3],
+        [[f32; 4]; 4],
+        [[f32; 3]; 2],
+        [[f32; 2]; 3],
+        [[f32; 4]; 2],
+        [[f32; 2]; 4],
+        [[f32; 4]; 3],
+        [[f32; 3]; 4],
+    }
+    RowMajorMatrix(true) {
+        [[f32; 2]; 2],
+        [[f32; 3]; 3],
+        [[f32; 4]; 4],
+        [[f32; 2]; 3],
+        [[f32; 3]; 2],
+        [[f32; 2]; 4],
+        [[f32; 4]; 2],
+        [[f32; 3]; 4],
+        [[f32; 4]; 3],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(size: u32, align: u32) -> TypeLayout {
+        TypeLayout { size, align }
+    }
+
+    #[test]
+    fn scalars_and_vectors() {
+        assert_eq!(layout(4, 4), <bool as BlockMember>::STD140);
+        assert_eq!(layout(8, 8), <f64 as BlockMember>::STD430);
+        assert_eq!(layout(8, 8), <[f32; 2] as BlockMember>::STD140);
+        assert_eq!(layout(12, 16), <[f32; 3] as BlockMember>::STD140);
+        assert_eq!(layout(24, 32), <[f64; 3] as BlockMember>::STD430);
+    }
+
+    #[test]
+    fn arrays_round_up_in_std140_only() {
+        assert_eq!(layout(64, 16), <[[f32; 1]; 4] as BlockMember>::STD140);
+        assert_eq!(layout(16, 4), <[[f32; 1]; 4] as BlockMember>::STD430);
+        assert_eq!(layout(48, 16), <[[f32; 3]; 3] as BlockMember>::STD430);
+        assert_eq!(layout(32, 16), <[[u32; 2]; 2] as BlockMember>::STD140);
+        assert_eq!(layout(16, 8), <[[u32; 2]; 2] as BlockMember>::STD430);
+    }
+
+    #[test]
+    fn matrices() {
+        type Mat2 = ColMajorMatrix<[[f32; 2]; 2]>;
+        type Mat3 = ColMajorMatrix<[[f32; 3]; 3]>;
+        type Mat2x3 = ColMajorMatrix<[[f32; 3]; 2]>;
+        type RowMat2x3 = RowMajorMatrix<[[f32; 2]; 3]>;
+        assert_eq!(layout(32, 16), Mat2::STD140);
+        assert_eq!(layout(16, 8), Mat2::STD430);
+        assert_eq!(layout(48, 16), Mat3::STD430);
+        assert_eq!(layout(32, 16), Mat2x3::STD140);
+        assert_eq!(layout(48, 16), RowMat2x3::STD140);
+        assert_eq!(layout(24, 8), RowMat2x3::STD430);
+    }
+
+    #[test]
+    fn structures() {
+        // struct { float a; vec3 b; float c; vec2 d[2]; }
+        let members = [
+            <f32 as BlockMember>::STD140,
+            <[f32; 3] as BlockMember>::STD140,
+            <f32 as BlockMember>::STD140,
+            <[[f32; 2]; 2] as BlockMember>::STD140,
+        ];
+        let offsets: Vec<u32> = (0..members.len())
+            .map(|i| LayoutStandard::Std140.member_offset(&members, i))
+            .collect();
+        assert_eq!(vec![0, 16, 28, 32], offsets);
+        assert_eq!(layout(64, 16), LayoutStandard::Std140.structure(&members));
+
+        let members = [
+            <f32 as BlockMember>::STD430,
+            <[f32; 3] as BlockMember>::STD430,
+            <f32 as BlockMember>::STD430,
+            <[[f32; 2]; 2] as BlockMember>::STD430,
+        ];
+        let offsets: Vec<u32> = (0..members.len())
+            .map(|i| LayoutStandard::Std430.member_offset(&members, i))
+            .collect();
+        assert_eq!(vec![0, 16, 28, 32], offsets);
+        assert_eq!(layout(48, 16), LayoutStandard::Std430.structure(&members));
+
+        let small = [<f32 as BlockMember>::STD430];
+        assert_eq!(layout(16, 16), LayoutStandard::Std140.structure(&small));
+        assert_eq!(layout(4, 4), LayoutStandard::Std430.structure(&small));
+    }
+
+    #[test]
+    fn padded_representations_match_layouts() {
+        fn check<T: AsStd140>() {
+            assert_eq!(T::STD140.size as usize, std::mem::size_of::<T::Std140>());
+            assert_eq!(
+                LayoutStandard::Std140.array_stride(T::STD140) as usize,
+                std::mem::size_of::<T::Std140Element>()
+            );
+        }
+        check::<bool>();
+        check::<f32>();
+        check::<f64>();
+        check::<[f32; 3]>();
+        check::<[f64; 3]>();
+        check::<[[f32; 3]; 5]>();
+        check::<[[f64; 1]; 3]>();
+        check::<ColMajorMatrix<[[f32; 3]; 3]>>();
+        check::<RowMajorMatrix<[[f32; 4]; 2]>>();
+        check::<[ColMajorMatrix<[[f32; 2]; 2]>; 2]>();
+
+        assert_eq!(
+            [[1.0, 2.0, 3.0, 0.0], [4.0, 5.0, 6.0, 0.0]],
+            ColMajorMatrix([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]).as_std140()
+        );
+    }
+}
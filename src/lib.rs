@@ -2,29 +2,63 @@
 mod macros;
 
 mod attribute_format;
+mod block_layout;
+mod cached_gl;
 pub mod convert;
+mod framebuffer_desc;
 pub mod gl;
+mod indirect;
+mod layout;
 pub mod locations;
+mod matrix;
 pub mod names;
 pub mod num;
+mod packed;
 pub mod params;
+pub mod profiler;
+mod program_cache;
+mod reloadable_program;
+mod render_state;
+mod shader_binary;
+mod shader_diagnostic;
+mod shader_preprocessor;
 pub mod string;
 pub mod symbols;
 pub mod types;
+mod vertex_input;
+mod vertex_layout;
 
 pub use attribute_format::*;
+pub use block_layout::*;
+pub use cached_gl::*;
 pub use convert::*;
+pub use framebuffer_desc::*;
+pub use indirect::*;
+pub use layout::*;
 pub use locations::*;
+pub use matrix::*;
 pub use names::*;
+pub use packed::*;
 pub use params::*;
+pub use program_cache::*;
+pub use reloadable_program::*;
+pub use render_state::*;
+pub use shader_binary::*;
+pub use shader_diagnostic::*;
+pub use shader_preprocessor::*;
 pub use symbols::*;
 pub use types::*;
+pub use vertex_input::*;
+pub use vertex_layout::*;
+
+#[cfg(feature = "derive")]
+pub use gl_typed_derive::{Std140, VertexLayout};
 
 use std::convert::{TryFrom, TryInto};
 use std::ffi::CStr;
 use std::mem::{ManuallyDrop, MaybeUninit};
-use std::num::NonZeroU64;
 use std::os::raw::{c_char, c_void};
+use std::time::Duration;
 
 macro_rules! impl_uniform_setters {
     ($fn1: ident, $glfn1: ident, $fn2: ident, $glfn2: ident, $fn3: ident, $glfn3: ident, $fn4: ident, $glfn4: ident, $ty: ty) => {
@@ -175,6 +209,21 @@ impl Gl {
         value.assume_init()
     }
 
+    #[inline]
+    pub unsafe fn get_max_viewports(&self) -> MaxViewports {
+        let mut value = MaybeUninit::<i32>::uninit();
+        self.gl.GetIntegerv(gl::MAX_VIEWPORTS, value.as_mut_ptr());
+        MaxViewports(value.assume_init().try_into().unwrap())
+    }
+
+    #[inline]
+    pub unsafe fn get_max_color_attachments(&self) -> MaxColorAttachments {
+        let mut value = MaybeUninit::<i32>::uninit();
+        self.gl
+            .GetIntegerv(gl::MAX_COLOR_ATTACHMENTS, value.as_mut_ptr());
+        MaxColorAttachments(value.assume_init().try_into().unwrap())
+    }
+
     #[inline]
     pub unsafe fn get_uniform_buffer_offset_alignment(&self) -> i32 {
         let mut value = MaybeUninit::<i32>::uninit();
@@ -198,6 +247,21 @@ impl Gl {
         self.gl.GetError()
     }
 
+    #[inline]
+    pub unsafe fn push_debug_group(&self, source: impl Into<DebugSource>, id: u32, message: &str) {
+        self.gl.PushDebugGroup(
+            source.into() as u32,
+            id,
+            message.len() as i32,
+            message.as_ptr() as *const i8,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn pop_debug_group(&self) {
+        self.gl.PopDebugGroup();
+    }
+
     // Drawing.
     #[inline]
     pub unsafe fn enable<C>(&self, cap: C)
@@ -215,6 +279,38 @@ impl Gl {
         self.gl.Disable(cap.into() as u32);
     }
 
+    #[inline]
+    pub unsafe fn is_enabled<C>(&self, cap: C) -> bool
+    where
+        C: Into<Capability>,
+    {
+        self.gl.IsEnabled(cap.into() as u32) != gl::FALSE
+    }
+
+    #[inline]
+    pub unsafe fn enablei<C>(&self, cap: C, index: u32)
+    where
+        C: Into<IndexedCapability>,
+    {
+        self.gl.Enablei(cap.into() as u32, index);
+    }
+
+    #[inline]
+    pub unsafe fn disablei<C>(&self, cap: C, index: u32)
+    where
+        C: Into<IndexedCapability>,
+    {
+        self.gl.Disablei(cap.into() as u32, index);
+    }
+
+    #[inline]
+    pub unsafe fn is_enabledi<C>(&self, cap: C, index: u32) -> bool
+    where
+        C: Into<IndexedCapability>,
+    {
+        self.gl.IsEnabledi(cap.into() as u32, index) != gl::FALSE
+    }
+
     #[inline]
     pub unsafe fn polygon_mode<F, M>(&self, face: F, mode: M)
     where
@@ -255,6 +351,75 @@ impl Gl {
         self.gl.Viewport(x, y, width, height);
     }
 
+    #[inline]
+    pub unsafe fn viewport_indexedf(
+        &self,
+        index: ViewportIndex,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    ) {
+        self.gl
+            .ViewportIndexedf(index.to_u32(), x, y, width, height);
+    }
+
+    /// Every element holds `[x, y, width, height]`. Panics if the viewports
+    /// `first` up to `first + viewports.len()` do not all exist.
+    #[inline]
+    pub unsafe fn viewport_arrayv(
+        &self,
+        first: ViewportIndex,
+        viewports: &[[f32; 4]],
+        max: MaxViewports,
+    ) {
+        assert!(first.to_u32() as usize + viewports.len() <= max.to_u32() as usize);
+        self.gl.ViewportArrayv(
+            first.to_u32(),
+            viewports.len() as i32,
+            viewports.as_ptr() as *const f32,
+        );
+    }
+
+    /// Every element holds `[x, y, width, height]`. Panics if the viewports
+    /// `first` up to `first + scissors.len()` do not all exist.
+    #[inline]
+    pub unsafe fn scissor_arrayv(
+        &self,
+        first: ViewportIndex,
+        scissors: &[[i32; 4]],
+        max: MaxViewports,
+    ) {
+        assert!(first.to_u32() as usize + scissors.len() <= max.to_u32() as usize);
+        self.gl.ScissorArrayv(
+            first.to_u32(),
+            scissors.len() as i32,
+            scissors.as_ptr() as *const i32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn depth_range_indexed(&self, index: ViewportIndex, n: f64, f: f64) {
+        self.gl.DepthRangeIndexed(index.to_u32(), n, f);
+    }
+
+    /// Every element holds `[n, f]`. Panics if the viewports `first` up to
+    /// `first + depth_ranges.len()` do not all exist.
+    #[inline]
+    pub unsafe fn depth_range_arrayv(
+        &self,
+        first: ViewportIndex,
+        depth_ranges: &[[f64; 2]],
+        max: MaxViewports,
+    ) {
+        assert!(first.to_u32() as usize + depth_ranges.len() <= max.to_u32() as usize);
+        self.gl.DepthRangeArrayv(
+            first.to_u32(),
+            depth_ranges.len() as i32,
+            depth_ranges.as_ptr() as *const f64,
+        );
+    }
+
     #[inline]
     pub unsafe fn clear_color(&self, r: f32, g: f32, b: f32, a: f32) {
         self.gl.ClearColor(r, g, b, a);
@@ -322,6 +487,183 @@ impl Gl {
         self.gl.BlendFunci(draw_buffer, src.into() as u32, dst.into() as u32);
     }
 
+    #[inline]
+    pub unsafe fn blend_func_separate(
+        &self,
+        src_rgb: impl Into<BlendFactor>,
+        dst_rgb: impl Into<BlendFactor>,
+        src_alpha: impl Into<BlendFactor>,
+        dst_alpha: impl Into<BlendFactor>,
+    ) {
+        self.gl.BlendFuncSeparate(
+            src_rgb.into() as u32,
+            dst_rgb.into() as u32,
+            src_alpha.into() as u32,
+            dst_alpha.into() as u32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn blend_func_separatei(
+        &self,
+        draw_buffer: u32,
+        src_rgb: impl Into<BlendFactor>,
+        dst_rgb: impl Into<BlendFactor>,
+        src_alpha: impl Into<BlendFactor>,
+        dst_alpha: impl Into<BlendFactor>,
+    ) {
+        self.gl.BlendFuncSeparatei(
+            draw_buffer,
+            src_rgb.into() as u32,
+            dst_rgb.into() as u32,
+            src_alpha.into() as u32,
+            dst_alpha.into() as u32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn blend_equation(&self, mode: impl Into<BlendEquation>) {
+        self.gl.BlendEquation(mode.into() as u32);
+    }
+
+    #[inline]
+    pub unsafe fn blend_equationi(&self, draw_buffer: u32, mode: impl Into<BlendEquation>) {
+        self.gl.BlendEquationi(draw_buffer, mode.into() as u32);
+    }
+
+    #[inline]
+    pub unsafe fn blend_equation_separate(
+        &self,
+        mode_rgb: impl Into<BlendEquation>,
+        mode_alpha: impl Into<BlendEquation>,
+    ) {
+        self.gl
+            .BlendEquationSeparate(mode_rgb.into() as u32, mode_alpha.into() as u32);
+    }
+
+    #[inline]
+    pub unsafe fn blend_equation_separatei(
+        &self,
+        draw_buffer: u32,
+        mode_rgb: impl Into<BlendEquation>,
+        mode_alpha: impl Into<BlendEquation>,
+    ) {
+        self.gl.BlendEquationSeparatei(
+            draw_buffer,
+            mode_rgb.into() as u32,
+            mode_alpha.into() as u32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn blend_color(&self, r: f32, g: f32, b: f32, a: f32) {
+        self.gl.BlendColor(r, g, b, a);
+    }
+
+    #[inline]
+    pub unsafe fn stencil_mask_separate(&self, face: impl Into<StencilFace>, mask: u32) {
+        self.gl.StencilMaskSeparate(face.into() as u32, mask);
+    }
+
+    #[inline]
+    pub unsafe fn stencil_func(&self, func: impl Into<StencilFunc>, reference: i32, mask: u32) {
+        self.gl.StencilFunc(func.into() as u32, reference, mask);
+    }
+
+    #[inline]
+    pub unsafe fn stencil_func_separate(
+        &self,
+        face: impl Into<StencilFace>,
+        func: impl Into<StencilFunc>,
+        reference: i32,
+        mask: u32,
+    ) {
+        self.gl
+            .StencilFuncSeparate(face.into() as u32, func.into() as u32, reference, mask);
+    }
+
+    #[inline]
+    pub unsafe fn stencil_op(
+        &self,
+        stencil_fail: impl Into<StencilOp>,
+        depth_fail: impl Into<StencilOp>,
+        depth_pass: impl Into<StencilOp>,
+    ) {
+        self.gl.StencilOp(
+            stencil_fail.into() as u32,
+            depth_fail.into() as u32,
+            depth_pass.into() as u32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn stencil_op_separate(
+        &self,
+        face: impl Into<StencilFace>,
+        stencil_fail: impl Into<StencilOp>,
+        depth_fail: impl Into<StencilOp>,
+        depth_pass: impl Into<StencilOp>,
+    ) {
+        self.gl.StencilOpSeparate(
+            face.into() as u32,
+            stencil_fail.into() as u32,
+            depth_fail.into() as u32,
+            depth_pass.into() as u32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn scissor(&self, x: i32, y: i32, width: i32, height: i32) {
+        self.gl.Scissor(x, y, width, height);
+    }
+
+    #[inline]
+    pub unsafe fn scissor_indexed(
+        &self,
+        index: ViewportIndex,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) {
+        self.gl.ScissorIndexed(index.to_u32(), x, y, width, height);
+    }
+
+    #[inline]
+    pub unsafe fn polygon_offset(&self, factor: f32, units: f32) {
+        self.gl.PolygonOffset(factor, units);
+    }
+
+    #[inline]
+    pub unsafe fn line_width(&self, width: f32) {
+        self.gl.LineWidth(width);
+    }
+
+    #[inline]
+    pub unsafe fn point_size(&self, size: f32) {
+        self.gl.PointSize(size);
+    }
+
+    #[inline]
+    pub unsafe fn sample_coverage(&self, value: f32, invert: bool) {
+        self.gl.SampleCoverage(value, invert as u8);
+    }
+
+    #[inline]
+    pub unsafe fn sample_maski(&self, mask_number: u32, mask: u32) {
+        self.gl.SampleMaski(mask_number, mask);
+    }
+
+    #[inline]
+    pub unsafe fn logic_op(&self, op: impl Into<LogicOp>) {
+        self.gl.LogicOp(op.into() as u32);
+    }
+
+    #[inline]
+    pub unsafe fn primitive_restart_index(&self, index: u32) {
+        self.gl.PrimitiveRestartIndex(index);
+    }
+
     #[inline]
     pub unsafe fn pixel_store_pack_alignment(&self, alignment: PixelAlignment) {
         self.gl.PixelStorei(gl::PACK_ALIGNMENT, alignment.to_gl())
@@ -463,89 +805,332 @@ impl Gl {
     }
 
     #[inline]
-    pub unsafe fn draw_elements<M, T>(&self, mode: M, count: u32, ty: T, offset: u32)
-    where
+    pub unsafe fn draw_arrays_instanced<M>(
+        &self,
+        mode: M,
+        first: usize,
+        count: usize,
+        instance_count: u32,
+    ) where
         M: Into<DrawMode>,
-        T: Into<DrawElementsType>,
     {
-        self.gl.DrawElements(
+        self.gl.DrawArraysInstanced(
             mode.into() as u32,
+            first as i32,
             count as i32,
-            ty.into() as u32,
+            instance_count as i32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn draw_arrays_instanced_base_instance<M>(
+        &self,
+        mode: M,
+        first: usize,
+        count: usize,
+        instance_count: u32,
+        base_instance: u32,
+    ) where
+        M: Into<DrawMode>,
+    {
+        self.gl.DrawArraysInstancedBaseInstance(
+            mode.into() as u32,
+            first as i32,
+            count as i32,
+            instance_count as i32,
+            base_instance,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn draw_arrays_indirect<M>(&self, mode: M, offset: usize)
+    where
+        M: Into<DrawMode>,
+    {
+        self.gl
+            .DrawArraysIndirect(mode.into() as u32, offset as *const c_void);
+    }
+
+    #[inline]
+    pub unsafe fn multi_draw_arrays<M>(&self, mode: M, firsts: &[u32], counts: &[u32])
+    where
+        M: Into<DrawMode>,
+    {
+        let draw_count = firsts.len();
+        assert_eq!(draw_count, counts.len());
+        self.gl.MultiDrawArrays(
+            mode.into() as u32,
+            firsts.as_ptr() as *const i32,
+            counts.as_ptr() as *const i32,
+            draw_count as i32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn multi_draw_arrays_indirect<M>(
+        &self,
+        mode: M,
+        offset: usize,
+        draw_count: i32,
+        stride: i32,
+    ) where
+        M: Into<DrawMode>,
+    {
+        self.gl.MultiDrawArraysIndirect(
+            mode.into() as u32,
             offset as *const c_void,
+            draw_count,
+            stride,
         );
     }
 
+    /// Reads the draw count from `draw_count_offset` in the buffer bound to
+    /// `PARAMETER_BUFFER`. Requires OpenGL 4.6 or `GL_ARB_indirect_parameters`.
     #[inline]
-    pub unsafe fn draw_elements_base_vertex<M, T>(
+    pub unsafe fn multi_draw_arrays_indirect_count<M>(
         &self,
         mode: M,
-        count: u32,
-        ty: T,
         offset: usize,
-        base_vertex: u32,
+        draw_count_offset: usize,
+        max_draw_count: i32,
+        stride: i32,
     ) where
         M: Into<DrawMode>,
+    {
+        self.gl.MultiDrawArraysIndirectCountARB(
+            mode.into() as u32,
+            offset as *const c_void,
+            draw_count_offset as isize,
+            max_draw_count,
+            stride,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn draw_elements<M, T>(&self, mode: M, count: u32, ty: T, offset: usize)
+    where
+        M: Into<DrawMode>,
         T: Into<DrawElementsType>,
     {
-        self.gl.DrawElementsBaseVertex(
+        self.gl.DrawElements(
             mode.into() as u32,
             count as i32,
             ty.into() as u32,
             offset as *const c_void,
-            base_vertex as i32,
         );
     }
 
     #[inline]
-    pub unsafe fn draw_elements_instanced_base_vertex<M, T>(
+    pub unsafe fn draw_elements_instanced<M, T>(
         &self,
         mode: M,
         count: u32,
         ty: T,
         offset: usize,
         instance_count: u32,
-        base_vertex: u32,
     ) where
         M: Into<DrawMode>,
         T: Into<DrawElementsType>,
     {
-        self.gl.DrawElementsInstancedBaseVertex(
+        self.gl.DrawElementsInstanced(
             mode.into() as u32,
             count as i32,
             ty.into() as u32,
             offset as *const c_void,
             instance_count as i32,
-            base_vertex as i32,
         );
     }
 
     #[inline]
-    pub unsafe fn draw_elements_indirect<M, T>(&self, mode: M, ty: T, offset: usize)
-    where
+    pub unsafe fn draw_elements_base_vertex<M, T>(
+        &self,
+        mode: M,
+        count: u32,
+        ty: T,
+        offset: usize,
+        base_vertex: u32,
+    ) where
         M: Into<DrawMode>,
         T: Into<DrawElementsType>,
     {
-        self.gl.DrawElementsIndirect(
+        self.gl.DrawElementsBaseVertex(
             mode.into() as u32,
+            count as i32,
             ty.into() as u32,
             offset as *const c_void,
+            base_vertex as i32,
         );
     }
 
     #[inline]
-    pub unsafe fn multi_draw_elements_indirect<M, T>(
+    pub unsafe fn draw_elements_instanced_base_vertex<M, T>(
         &self,
         mode: M,
+        count: u32,
         ty: T,
         offset: usize,
-        draw_count: i32,
-        stride: i32,
+        instance_count: u32,
+        base_vertex: u32,
     ) where
         M: Into<DrawMode>,
         T: Into<DrawElementsType>,
     {
-        self.gl.MultiDrawElementsIndirect(
+        self.gl.DrawElementsInstancedBaseVertex(
+            mode.into() as u32,
+            count as i32,
+            ty.into() as u32,
+            offset as *const c_void,
+            instance_count as i32,
+            base_vertex as i32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn draw_elements_instanced_base_vertex_base_instance<M, T>(
+        &self,
+        mode: M,
+        count: u32,
+        ty: T,
+        offset: usize,
+        instance_count: u32,
+        base_vertex: u32,
+        base_instance: u32,
+    ) where
+        M: Into<DrawMode>,
+        T: Into<DrawElementsType>,
+    {
+        self.gl.DrawElementsInstancedBaseVertexBaseInstance(
+            mode.into() as u32,
+            count as i32,
+            ty.into() as u32,
+            offset as *const c_void,
+            instance_count as i32,
+            base_vertex as i32,
+            base_instance,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn draw_range_elements<M, T>(
+        &self,
+        mode: M,
+        start: u32,
+        end: u32,
+        count: u32,
+        ty: T,
+        offset: usize,
+    ) where
+        M: Into<DrawMode>,
+        T: Into<DrawElementsType>,
+    {
+        self.gl.DrawRangeElements(
+            mode.into() as u32,
+            start,
+            end,
+            count as i32,
+            ty.into() as u32,
+            offset as *const c_void,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn draw_range_elements_base_vertex<M, T>(
+        &self,
+        mode: M,
+        start: u32,
+        end: u32,
+        count: u32,
+        ty: T,
+        offset: usize,
+        base_vertex: u32,
+    ) where
+        M: Into<DrawMode>,
+        T: Into<DrawElementsType>,
+    {
+        self.gl.DrawRangeElementsBaseVertex(
+            mode.into() as u32,
+            start,
+            end,
+            count as i32,
+            ty.into() as u32,
+            offset as *const c_void,
+            base_vertex as i32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn multi_draw_elements<M, T>(
+        &self,
+        mode: M,
+        counts: &[u32],
+        ty: T,
+        offsets: &[usize],
+    ) where
+        M: Into<DrawMode>,
+        T: Into<DrawElementsType>,
+    {
+        let draw_count = counts.len();
+        assert_eq!(draw_count, offsets.len());
+        self.gl.MultiDrawElements(
+            mode.into() as u32,
+            counts.as_ptr() as *const i32,
+            ty.into() as u32,
+            offsets.as_ptr() as *const *const c_void,
+            draw_count as i32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn multi_draw_elements_base_vertex<M, T>(
+        &self,
+        mode: M,
+        counts: &[u32],
+        ty: T,
+        offsets: &[usize],
+        base_vertices: &[u32],
+    ) where
+        M: Into<DrawMode>,
+        T: Into<DrawElementsType>,
+    {
+        let draw_count = counts.len();
+        assert_eq!(draw_count, offsets.len());
+        assert_eq!(draw_count, base_vertices.len());
+        self.gl.MultiDrawElementsBaseVertex(
+            mode.into() as u32,
+            counts.as_ptr() as *const i32,
+            ty.into() as u32,
+            offsets.as_ptr() as *const *const c_void,
+            draw_count as i32,
+            base_vertices.as_ptr() as *const i32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn draw_elements_indirect<M, T>(&self, mode: M, ty: T, offset: usize)
+    where
+        M: Into<DrawMode>,
+        T: Into<DrawElementsType>,
+    {
+        self.gl.DrawElementsIndirect(
+            mode.into() as u32,
+            ty.into() as u32,
+            offset as *const c_void,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn multi_draw_elements_indirect<M, T>(
+        &self,
+        mode: M,
+        ty: T,
+        offset: usize,
+        draw_count: i32,
+        stride: i32,
+    ) where
+        M: Into<DrawMode>,
+        T: Into<DrawElementsType>,
+    {
+        self.gl.MultiDrawElementsIndirect(
             mode.into() as u32,
             ty.into() as u32,
             offset as *const c_void,
@@ -554,6 +1139,31 @@ impl Gl {
         );
     }
 
+    /// Reads the draw count from `draw_count_offset` in the buffer bound to
+    /// `PARAMETER_BUFFER`. Requires OpenGL 4.6 or `GL_ARB_indirect_parameters`.
+    #[inline]
+    pub unsafe fn multi_draw_elements_indirect_count<M, T>(
+        &self,
+        mode: M,
+        ty: T,
+        offset: usize,
+        draw_count_offset: usize,
+        max_draw_count: i32,
+        stride: i32,
+    ) where
+        M: Into<DrawMode>,
+        T: Into<DrawElementsType>,
+    {
+        self.gl.MultiDrawElementsIndirectCountARB(
+            mode.into() as u32,
+            ty.into() as u32,
+            offset as *const c_void,
+            draw_count_offset as isize,
+            max_draw_count,
+            stride,
+        );
+    }
+
     // Shaders.
 
     #[inline]
@@ -742,105 +1352,417 @@ impl Gl {
     }
 
     #[inline]
-    pub unsafe fn dispatch_compute(&self, num_groups_x: u32, num_groups_y: u32, num_groups_z: u32) {
-        self.gl
-            .DispatchCompute(num_groups_x, num_groups_y, num_groups_z);
+    pub unsafe fn get_program_interfaceiv<I, P>(
+        &self,
+        program_name: ProgramName,
+        interface: I,
+        _param: P,
+    ) -> P::Value
+    where
+        I: Into<ProgramInterface>,
+        P: get_program_interfaceiv_param::Variant,
+        <P::Value as TryFrom<P::Intermediate>>::Error: std::fmt::Debug,
+    {
+        let mut value = MaybeUninit::<i32>::uninit();
+        self.gl.GetProgramInterfaceiv(
+            program_name.to_u32(),
+            interface.into() as u32,
+            P::VALUE,
+            value.as_mut_ptr(),
+        );
+        P::Intermediate::cast_from(value.assume_init())
+            .try_into()
+            .unwrap()
     }
 
+    /// Writes the values of `props` into `values` and returns the number of
+    /// values written. Most properties produce a single value but
+    /// `ActiveVariables` produces `NumActiveVariables` values.
     #[inline]
-    pub unsafe fn dispatch_compute_indirect(&self, byte_offset: usize) {
-        self.gl.DispatchComputeIndirect(byte_offset as isize);
+    pub unsafe fn get_program_resourceiv<I>(
+        &self,
+        program_name: ProgramName,
+        interface: I,
+        index: u32,
+        props: &[ProgramResourceProperty],
+        values: &mut [i32],
+    ) -> usize
+    where
+        I: Into<ProgramInterface>,
+    {
+        let mut length = MaybeUninit::<i32>::uninit();
+        self.gl.GetProgramResourceiv(
+            program_name.to_u32(),
+            interface.into() as u32,
+            index,
+            props.len() as i32,
+            props.as_ptr() as *const u32,
+            values.len() as i32,
+            length.as_mut_ptr(),
+            values.as_mut_ptr(),
+        );
+        length.assume_init() as usize
     }
 
-    // Textures.
-
     #[inline]
-    pub unsafe fn create_texture<K>(&self, kind: K) -> TextureName
+    pub unsafe fn get_program_resource_name<I>(
+        &self,
+        program_name: ProgramName,
+        interface: I,
+        index: u32,
+    ) -> String
     where
-        K: Into<TextureTarget>,
+        I: Into<ProgramInterface>,
     {
-        self.try_create_texture(kind).unwrap()
+        String::from_utf8(self.get_program_resource_name_bytes(program_name, interface, index))
+            .unwrap()
     }
 
     #[inline]
-    pub unsafe fn try_create_texture<K>(
+    pub unsafe fn get_program_resource_name_bytes<I>(
         &self,
-        kind: K,
-    ) -> Result<TextureName, ReceivedInvalidTextureName>
+        program_name: ProgramName,
+        interface: I,
+        index: u32,
+    ) -> Vec<u8>
     where
-        K: Into<TextureTarget>,
+        I: Into<ProgramInterface>,
     {
-        let mut name = MaybeUninit::<u32>::uninit();
-        self.gl
-            .CreateTextures(kind.into() as u32, 1, name.as_mut_ptr());
-        TextureName::new(name.assume_init())
+        let interface = interface.into();
+        let mut name_length = 0;
+        self.get_program_resourceiv(
+            program_name,
+            interface,
+            index,
+            &[ProgramResourceProperty::NameLength],
+            std::slice::from_mut(&mut name_length),
+        );
+        let mut buffer = Vec::with_capacity(name_length as usize);
+        let mut length = MaybeUninit::<i32>::uninit();
+        self.gl.GetProgramResourceName(
+            program_name.to_u32(),
+            interface as u32,
+            index,
+            buffer.capacity() as i32,
+            length.as_mut_ptr(),
+            buffer.as_mut_ptr() as *mut i8,
+        );
+        let length = length.assume_init();
+        assert!(length >= 0 && length <= buffer.capacity() as i32);
+        buffer.set_len(length as usize);
+        buffer
     }
 
+    /// Returns `INVALID_INDEX` when `name` does not identify an active
+    /// resource of `interface`.
     #[inline]
-    pub unsafe fn delete_texture(&self, name: TextureName) {
-        self.gl.DeleteTextures(1, &ManuallyDrop::new(name).to_u32());
+    pub unsafe fn get_program_resource_index<I>(
+        &self,
+        program_name: ProgramName,
+        interface: I,
+        name: &CStr,
+    ) -> u32
+    where
+        I: Into<ProgramInterface>,
+    {
+        self.gl.GetProgramResourceIndex(
+            program_name.to_u32(),
+            interface.into() as u32,
+            name.as_ptr(),
+        )
     }
 
-    #[deprecated]
     #[inline]
-    pub unsafe fn gen_textures(&self, names: &mut [Option<TextureName>]) {
+    pub unsafe fn program_parameteri<P, V>(&self, name: ProgramName, _param: P, value: V)
+    where
+        P: program_parameteri_param::Variant,
+        V: Into<P::Value>,
+    {
         self.gl
-            .GenTextures(names.len() as i32, names.as_mut_ptr() as *mut u32);
+            .ProgramParameteri(name.to_u32(), P::VALUE, value.into().into().cast_into());
     }
 
-    #[deprecated]
+    /// Creates, compiles and links a separable program from a single shader
+    /// stage. OpenGL requires the sources to be null-terminated.
     #[inline]
-    pub unsafe fn delete_textures(&self, names: &mut [Option<TextureName>]) {
-        self.gl
-            .DeleteTextures(names.len() as i32, names.as_ptr() as *const u32);
+    pub unsafe fn create_shader_programv<K>(&self, kind: K, sources: &[&CStr]) -> ProgramName
+    where
+        K: Into<ShaderKind>,
+    {
+        self.try_create_shader_programv(kind, sources).unwrap()
     }
 
-    #[deprecated]
     #[inline]
-    pub unsafe fn active_texture<U>(&self, unit: U)
+    pub unsafe fn try_create_shader_programv<K>(
+        &self,
+        kind: K,
+        sources: &[&CStr],
+    ) -> Result<ProgramName, ReceivedInvalidProgramName>
     where
-        U: Into<TextureUnit>,
+        K: Into<ShaderKind>,
     {
-        self.gl.ActiveTexture(unit.into().to_u32());
+        let pointers: Vec<*const c_char> = sources.iter().map(|source| source.as_ptr()).collect();
+        ProgramName::new(self.gl.CreateShaderProgramv(
+            kind.into() as u32,
+            pointers.len() as i32,
+            pointers.as_ptr(),
+        ))
     }
 
+    // Program pipelines.
+
     #[inline]
-    pub unsafe fn bind_texture_unit(&self, unit: u32, texture_name: TextureName) {
-        self.gl.BindTextureUnit(unit, texture_name.to_u32());
+    pub unsafe fn create_program_pipeline(&self) -> ProgramPipelineName {
+        self.try_create_program_pipeline().unwrap()
     }
 
     #[inline]
-    pub unsafe fn bind_texture<T>(&self, target: T, name: TextureName)
-    where
-        T: Into<TextureTarget>,
-    {
-        self.gl.BindTexture(target.into() as u32, name.to_u32());
+    pub unsafe fn try_create_program_pipeline(
+        &self,
+    ) -> Result<ProgramPipelineName, ReceivedInvalidProgramPipelineName> {
+        let mut name = MaybeUninit::<u32>::uninit();
+        self.gl.CreateProgramPipelines(1, name.as_mut_ptr());
+        ProgramPipelineName::new(name.assume_init())
     }
 
     #[inline]
-    pub unsafe fn unbind_texture<T>(&self, target: T)
-    where
-        T: Into<TextureTarget>,
-    {
-        self.gl.BindTexture(target.into() as u32, 0);
+    pub unsafe fn delete_program_pipeline(&self, name: ProgramPipelineName) {
+        self.gl
+            .DeleteProgramPipelines(1, &ManuallyDrop::new(name).to_u32());
     }
 
     #[inline]
-    pub unsafe fn texture_parameteri<P, V>(&self, name: TextureName, _param: P, value: V)
-    where
-        P: tex_parameteri_param::Variant,
-        V: Into<P::Value>,
-    {
-        self.gl
-            .TextureParameteri(name.to_u32(), P::VALUE, value.into().into().cast_into());
+    pub unsafe fn bind_program_pipeline(&self, name: ProgramPipelineName) {
+        self.gl.BindProgramPipeline(name.to_u32());
     }
 
     #[inline]
-    pub unsafe fn texture_parameterf<P, V>(&self, name: TextureName, _param: P, value: V)
-    where
-        P: tex_parameterf_param::Variant,
-        V: Into<P::Value>,
-    {
+    pub unsafe fn unbind_program_pipeline(&self) {
+        self.gl.BindProgramPipeline(0);
+    }
+
+    #[inline]
+    pub unsafe fn use_program_stages(
+        &self,
+        pipeline_name: ProgramPipelineName,
+        stages: ShaderStageFlag,
+        program_name: ProgramName,
+    ) {
+        self.gl
+            .UseProgramStages(pipeline_name.to_u32(), stages.bits(), program_name.to_u32());
+    }
+
+    #[inline]
+    pub unsafe fn unuse_program_stages(
+        &self,
+        pipeline_name: ProgramPipelineName,
+        stages: ShaderStageFlag,
+    ) {
+        self.gl
+            .UseProgramStages(pipeline_name.to_u32(), stages.bits(), 0);
+    }
+
+    /// Selects the program that receives `glUniform*` calls while the pipeline
+    /// is bound.
+    #[inline]
+    pub unsafe fn active_shader_program(
+        &self,
+        pipeline_name: ProgramPipelineName,
+        program_name: ProgramName,
+    ) {
+        self.gl
+            .ActiveShaderProgram(pipeline_name.to_u32(), program_name.to_u32());
+    }
+
+    #[inline]
+    pub unsafe fn validate_program_pipeline(&self, name: ProgramPipelineName) {
+        self.gl.ValidateProgramPipeline(name.to_u32());
+    }
+
+    #[inline]
+    pub unsafe fn get_program_pipelineiv<P>(&self, name: ProgramPipelineName, _param: P) -> P::Value
+    where
+        P: get_program_pipelineiv_param::Variant,
+        <P::Value as TryFrom<P::Intermediate>>::Error: std::fmt::Debug,
+    {
+        let mut value = MaybeUninit::<i32>::uninit();
+        self.gl
+            .GetProgramPipelineiv(name.to_u32(), P::VALUE, value.as_mut_ptr());
+        P::Intermediate::cast_from(value.assume_init())
+            .try_into()
+            .unwrap()
+    }
+
+    #[inline]
+    pub unsafe fn get_program_pipeline_info_log(&self, name: ProgramPipelineName) -> String {
+        String::from_utf8(self.get_program_pipeline_info_log_bytes(name)).unwrap()
+    }
+
+    #[inline]
+    pub unsafe fn get_program_pipeline_info_log_bytes(&self, name: ProgramPipelineName) -> Vec<u8> {
+        let mut buffer = Vec::with_capacity(self.get_program_pipelineiv(name, INFO_LOG_LENGTH));
+        let mut length = MaybeUninit::<i32>::uninit();
+        self.gl.GetProgramPipelineInfoLog(
+            name.to_u32(),
+            buffer.capacity() as i32,
+            length.as_mut_ptr(),
+            buffer.as_mut_ptr() as *mut i8,
+        );
+        let length = length.assume_init();
+        assert!(length >= 0 && length <= buffer.capacity() as i32);
+        buffer.set_len(length as usize);
+        buffer
+    }
+
+    #[inline]
+    pub unsafe fn dispatch_compute(&self, num_groups_x: u32, num_groups_y: u32, num_groups_z: u32) {
+        self.gl
+            .DispatchCompute(num_groups_x, num_groups_y, num_groups_z);
+    }
+
+    #[inline]
+    pub unsafe fn dispatch_compute_indirect(&self, byte_offset: usize) {
+        self.gl.DispatchComputeIndirect(byte_offset as isize);
+    }
+
+    // Textures.
+
+    #[inline]
+    pub unsafe fn create_texture<K>(&self, kind: K) -> TextureName
+    where
+        K: Into<TextureTarget>,
+    {
+        self.try_create_texture(kind).unwrap()
+    }
+
+    #[inline]
+    pub unsafe fn try_create_texture<K>(
+        &self,
+        kind: K,
+    ) -> Result<TextureName, ReceivedInvalidTextureName>
+    where
+        K: Into<TextureTarget>,
+    {
+        let mut name = MaybeUninit::<u32>::uninit();
+        self.gl
+            .CreateTextures(kind.into() as u32, 1, name.as_mut_ptr());
+        TextureName::new(name.assume_init())
+    }
+
+    #[inline]
+    pub unsafe fn delete_texture(&self, name: TextureName) {
+        self.gl.DeleteTextures(1, &ManuallyDrop::new(name).to_u32());
+    }
+
+    #[deprecated]
+    #[inline]
+    pub unsafe fn gen_textures(&self, names: &mut [Option<TextureName>]) {
+        self.gl
+            .GenTextures(names.len() as i32, names.as_mut_ptr() as *mut u32);
+    }
+
+    #[deprecated]
+    #[inline]
+    pub unsafe fn delete_textures(&self, names: &mut [Option<TextureName>]) {
+        self.gl
+            .DeleteTextures(names.len() as i32, names.as_ptr() as *const u32);
+    }
+
+    #[deprecated]
+    #[inline]
+    pub unsafe fn active_texture<U>(&self, unit: U)
+    where
+        U: Into<TextureUnit>,
+    {
+        self.gl.ActiveTexture(unit.into().to_u32());
+    }
+
+    #[inline]
+    pub unsafe fn bind_texture_unit(&self, unit: u32, texture_name: TextureName) {
+        self.gl.BindTextureUnit(unit, texture_name.to_u32());
+    }
+
+    /// When `layered` is true all layers are bound and `layer` is ignored.
+    #[inline]
+    pub unsafe fn bind_image_texture(
+        &self,
+        unit: u32,
+        texture_name: TextureName,
+        level: i32,
+        layered: bool,
+        layer: u32,
+        access: impl Into<ImageAccess>,
+        format: impl Into<ImageFormat>,
+    ) {
+        self.gl.BindImageTexture(
+            unit,
+            texture_name.to_u32(),
+            level,
+            layered as u8,
+            layer as i32,
+            access.into() as u32,
+            format.into() as u32,
+        );
+    }
+
+    /// Binds `texture_names[i]` to unit `first_unit + i`. `None` unbinds.
+    #[inline]
+    pub unsafe fn bind_textures(&self, first_unit: u32, texture_names: &[Option<TextureName>]) {
+        self.gl.BindTextures(
+            first_unit,
+            texture_names.len() as i32,
+            texture_names.as_ptr() as *const u32,
+        );
+    }
+
+    /// Binds all layers of level 0 of `texture_names[i]` to image unit
+    /// `first_unit + i` with read/write access and the texture's own internal
+    /// format. `None` unbinds.
+    #[inline]
+    pub unsafe fn bind_image_textures(
+        &self,
+        first_unit: u32,
+        texture_names: &[Option<TextureName>],
+    ) {
+        self.gl.BindImageTextures(
+            first_unit,
+            texture_names.len() as i32,
+            texture_names.as_ptr() as *const u32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn bind_texture<T>(&self, target: T, name: TextureName)
+    where
+        T: Into<TextureTarget>,
+    {
+        self.gl.BindTexture(target.into() as u32, name.to_u32());
+    }
+
+    #[inline]
+    pub unsafe fn unbind_texture<T>(&self, target: T)
+    where
+        T: Into<TextureTarget>,
+    {
+        self.gl.BindTexture(target.into() as u32, 0);
+    }
+
+    #[inline]
+    pub unsafe fn texture_parameteri<P, V>(&self, name: TextureName, _param: P, value: V)
+    where
+        P: tex_parameteri_param::Variant,
+        V: Into<P::Value>,
+    {
+        self.gl
+            .TextureParameteri(name.to_u32(), P::VALUE, value.into().into().cast_into());
+    }
+
+    #[inline]
+    pub unsafe fn texture_parameterf<P, V>(&self, name: TextureName, _param: P, value: V)
+    where
+        P: tex_parameterf_param::Variant,
+        V: Into<P::Value>,
+    {
         self.gl
             .TextureParameterf(name.to_u32(), P::VALUE, value.into().into().cast_into());
     }
@@ -1644,6 +2566,70 @@ impl Gl {
             .DisableVertexArrayAttrib(vertex_array_name.to_u32(), attribute_location.to_u32());
     }
 
+    #[inline]
+    pub unsafe fn get_vertex_array_iv<P>(
+        &self,
+        vertex_array_name: VertexArrayName,
+        _param: P,
+    ) -> P::Value
+    where
+        P: get_vertex_array_iv_param::Variant,
+        <P::Value as TryFrom<P::Intermediate>>::Error: std::fmt::Debug,
+    {
+        let mut value = MaybeUninit::<i32>::uninit();
+        self.gl
+            .GetVertexArrayiv(vertex_array_name.to_u32(), P::VALUE, value.as_mut_ptr());
+        P::Intermediate::cast_from(value.assume_init())
+            .try_into()
+            .unwrap()
+    }
+
+    #[inline]
+    pub unsafe fn get_vertex_array_indexediv<P>(
+        &self,
+        vertex_array_name: VertexArrayName,
+        attribute_location: AttributeLocation,
+        _param: P,
+    ) -> P::Value
+    where
+        P: get_vertex_array_indexediv_param::Variant,
+        <P::Value as TryFrom<P::Intermediate>>::Error: std::fmt::Debug,
+    {
+        let mut value = MaybeUninit::<i32>::uninit();
+        self.gl.GetVertexArrayIndexediv(
+            vertex_array_name.to_u32(),
+            attribute_location.to_u32(),
+            P::VALUE,
+            value.as_mut_ptr(),
+        );
+        P::Intermediate::cast_from(value.assume_init())
+            .try_into()
+            .unwrap()
+    }
+
+    #[inline]
+    pub unsafe fn get_vertex_array_indexed64iv<P>(
+        &self,
+        vertex_array_name: VertexArrayName,
+        vertex_array_buffer_binding_index: VertexArrayBufferBindingIndex,
+        _param: P,
+    ) -> P::Value
+    where
+        P: get_vertex_array_indexed64iv_param::Variant,
+        <P::Value as TryFrom<P::Intermediate>>::Error: std::fmt::Debug,
+    {
+        let mut value = MaybeUninit::<i64>::uninit();
+        self.gl.GetVertexArrayIndexed64iv(
+            vertex_array_name.to_u32(),
+            vertex_array_buffer_binding_index.to_u32(),
+            P::VALUE,
+            value.as_mut_ptr(),
+        );
+        P::Intermediate::cast_from(value.assume_init())
+            .try_into()
+            .unwrap()
+    }
+
     // Framebuffer names.
 
     #[deprecated]
@@ -1747,6 +2733,26 @@ impl Gl {
         );
     }
 
+    #[inline]
+    pub unsafe fn named_framebuffer_texture_layer<FA>(
+        &self,
+        framebuffer_name: NonDefaultFramebufferName,
+        framebuffer_attachment: FA,
+        texture_name: TextureName,
+        level: i32,
+        layer: i32,
+    ) where
+        FA: Into<FramebufferAttachment>,
+    {
+        self.gl.NamedFramebufferTextureLayer(
+            framebuffer_name.to_u32(),
+            framebuffer_attachment.into().to_u32(),
+            texture_name.to_u32(),
+            level,
+            layer,
+        );
+    }
+
     #[deprecated]
     #[inline]
     pub unsafe fn framebuffer_renderbuffer<FT, FA, RT>(
@@ -1889,25 +2895,99 @@ impl Gl {
     }
 
     #[inline]
-    pub unsafe fn bind_buffer_base<T>(&self, target: T, index: u32, buffer_name: BufferName)
-    where
-        T: Into<BindBufferTarget>,
-    {
-        self.gl
-            .BindBufferBase(target.into() as u32, index, buffer_name.to_u32());
+    pub unsafe fn get_shader_storage_block_index(
+        &self,
+        program_name: ProgramName,
+        shader_storage_block_name: &CStr,
+    ) -> OptionShaderStorageBlockIndex {
+        OptionShaderStorageBlockIndex::from_u32(self.get_program_resource_index(
+            program_name,
+            ProgramInterface::ShaderStorageBlock,
+            shader_storage_block_name,
+        ))
     }
 
     #[inline]
-    pub unsafe fn bind_buffer_range<T>(
+    pub unsafe fn shader_storage_block_binding(
         &self,
-        target: T,
-        index: u32,
-        buffer_name: BufferName,
-        offset: usize,
-        size: usize,
-    ) where
-        T: Into<BindBufferTarget>,
-    {
+        program_name: ProgramName,
+        shader_storage_block_index: ShaderStorageBlockIndex,
+        shader_storage_block_binding: u32,
+    ) {
+        self.gl.ShaderStorageBlockBinding(
+            program_name.to_u32(),
+            shader_storage_block_index.to_u32(),
+            shader_storage_block_binding,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn get_active_atomic_counter_bufferiv<P>(
+        &self,
+        program_name: ProgramName,
+        buffer_index: u32,
+        _param: P,
+    ) -> P::Value
+    where
+        P: get_active_atomic_counter_bufferiv_param::Variant,
+        <P::Value as TryFrom<P::Intermediate>>::Error: std::fmt::Debug,
+    {
+        let mut value = MaybeUninit::<i32>::uninit();
+        self.gl.GetActiveAtomicCounterBufferiv(
+            program_name.to_u32(),
+            buffer_index,
+            P::VALUE,
+            value.as_mut_ptr(),
+        );
+        P::Intermediate::cast_from(value.assume_init())
+            .try_into()
+            .unwrap()
+    }
+
+    /// The indices of the active uniforms stored in an atomic counter buffer.
+    #[inline]
+    pub unsafe fn get_active_atomic_counter_buffer_counter_indices(
+        &self,
+        program_name: ProgramName,
+        buffer_index: u32,
+    ) -> Vec<u32> {
+        let count = self.get_active_atomic_counter_bufferiv(
+            program_name,
+            buffer_index,
+            ATOMIC_COUNTER_BUFFER_ACTIVE_ATOMIC_COUNTERS,
+        );
+        let mut indices = vec![0u32; count as usize];
+        if count > 0 {
+            self.gl.GetActiveAtomicCounterBufferiv(
+                program_name.to_u32(),
+                buffer_index,
+                gl::ATOMIC_COUNTER_BUFFER_ACTIVE_ATOMIC_COUNTER_INDICES,
+                indices.as_mut_ptr() as *mut i32,
+            );
+        }
+        indices
+    }
+
+    #[inline]
+    pub unsafe fn bind_buffer_base<T>(&self, target: T, index: u32, buffer_name: BufferName)
+    where
+        T: Into<BindBufferTarget>,
+    {
+        self.gl
+            .BindBufferBase(target.into() as u32, index, buffer_name.to_u32());
+    }
+
+    #[inline]
+    pub unsafe fn bind_buffer_range<T>(
+        &self,
+        target: T,
+        index: u32,
+        buffer_name: BufferName,
+        offset: usize,
+        size: usize,
+    ) where
+        T: Into<BindBufferTarget>,
+    {
         self.gl.BindBufferRange(
             target.into() as u32,
             index,
@@ -1917,6 +2997,240 @@ impl Gl {
         );
     }
 
+    /// Binds `buffer_names[i]` to index `first_index + i`. `None` unbinds.
+    #[inline]
+    pub unsafe fn bind_buffers_base<T>(
+        &self,
+        target: T,
+        first_index: u32,
+        buffer_names: &[Option<BufferName>],
+    ) where
+        T: Into<BindBufferTarget>,
+    {
+        self.gl.BindBuffersBase(
+            target.into() as u32,
+            first_index,
+            buffer_names.len() as i32,
+            buffer_names.as_ptr() as *const u32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn bind_buffers_range<T>(
+        &self,
+        target: T,
+        first_index: u32,
+        buffer_names: &[Option<BufferName>],
+        offsets: &[usize],
+        sizes: &[usize],
+    ) where
+        T: Into<BindBufferTarget>,
+    {
+        let count = buffer_names.len();
+        assert_eq!(count, offsets.len());
+        assert_eq!(count, sizes.len());
+        self.gl.BindBuffersRange(
+            target.into() as u32,
+            first_index,
+            count as i32,
+            buffer_names.as_ptr() as *const u32,
+            offsets.as_ptr() as *const isize,
+            sizes.as_ptr() as *const isize,
+        );
+    }
+
+    /// Binds `buffer_names[i]` to the vertex buffer binding `first + i` of
+    /// the bound vertex array. `None` unbinds.
+    #[inline]
+    pub unsafe fn bind_vertex_buffers(
+        &self,
+        first: VertexArrayBufferBindingIndex,
+        buffer_names: &[Option<BufferName>],
+        offsets: &[isize],
+        strides: &[i32],
+    ) {
+        let count = buffer_names.len();
+        assert_eq!(count, offsets.len());
+        assert_eq!(count, strides.len());
+        self.gl.BindVertexBuffers(
+            first.to_u32(),
+            count as i32,
+            buffer_names.as_ptr() as *const u32,
+            offsets.as_ptr(),
+            strides.as_ptr(),
+        );
+    }
+
+    // Transform feedback.
+
+    #[inline]
+    pub unsafe fn create_transform_feedback(&self) -> TransformFeedbackName {
+        self.try_create_transform_feedback().unwrap()
+    }
+
+    #[inline]
+    pub unsafe fn try_create_transform_feedback(
+        &self,
+    ) -> Result<TransformFeedbackName, ReceivedInvalidTransformFeedbackName> {
+        let mut name = MaybeUninit::<u32>::uninit();
+        self.gl.CreateTransformFeedbacks(1, name.as_mut_ptr());
+        TransformFeedbackName::new(name.assume_init())
+    }
+
+    #[inline]
+    pub unsafe fn delete_transform_feedback(&self, name: TransformFeedbackName) {
+        self.gl
+            .DeleteTransformFeedbacks(1, &ManuallyDrop::new(name).to_u32());
+    }
+
+    #[inline]
+    pub unsafe fn bind_transform_feedback(&self, name: TransformFeedbackName) {
+        self.gl
+            .BindTransformFeedback(gl::TRANSFORM_FEEDBACK, name.to_u32());
+    }
+
+    #[inline]
+    pub unsafe fn unbind_transform_feedback(&self) {
+        self.gl.BindTransformFeedback(gl::TRANSFORM_FEEDBACK, 0);
+    }
+
+    #[inline]
+    pub unsafe fn transform_feedback_buffer_base(
+        &self,
+        transform_feedback_name: TransformFeedbackName,
+        index: u32,
+        buffer_name: BufferName,
+    ) {
+        self.gl.TransformFeedbackBufferBase(
+            transform_feedback_name.to_u32(),
+            index,
+            buffer_name.to_u32(),
+        );
+    }
+
+    #[inline]
+    pub unsafe fn transform_feedback_buffer_range(
+        &self,
+        transform_feedback_name: TransformFeedbackName,
+        index: u32,
+        buffer_name: BufferName,
+        offset: usize,
+        size: usize,
+    ) {
+        self.gl.TransformFeedbackBufferRange(
+            transform_feedback_name.to_u32(),
+            index,
+            buffer_name.to_u32(),
+            offset as isize,
+            size as isize,
+        );
+    }
+
+    /// Must be called before linking the program.
+    #[inline]
+    pub unsafe fn transform_feedback_varyings<M>(
+        &self,
+        program_name: ProgramName,
+        varyings: &[&CStr],
+        buffer_mode: M,
+    ) where
+        M: Into<TransformFeedbackBufferMode>,
+    {
+        let pointers: Vec<*const c_char> =
+            varyings.iter().map(|varying| varying.as_ptr()).collect();
+        self.gl.TransformFeedbackVaryings(
+            program_name.to_u32(),
+            pointers.len() as i32,
+            pointers.as_ptr(),
+            buffer_mode.into() as u32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn begin_transform_feedback<M>(&self, primitive_mode: M)
+    where
+        M: Into<TransformFeedbackPrimitiveMode>,
+    {
+        self.gl.BeginTransformFeedback(primitive_mode.into() as u32);
+    }
+
+    #[inline]
+    pub unsafe fn end_transform_feedback(&self) {
+        self.gl.EndTransformFeedback();
+    }
+
+    #[inline]
+    pub unsafe fn pause_transform_feedback(&self) {
+        self.gl.PauseTransformFeedback();
+    }
+
+    #[inline]
+    pub unsafe fn resume_transform_feedback(&self) {
+        self.gl.ResumeTransformFeedback();
+    }
+
+    #[inline]
+    pub unsafe fn draw_transform_feedback<M>(
+        &self,
+        mode: M,
+        transform_feedback_name: TransformFeedbackName,
+    ) where
+        M: Into<DrawMode>,
+    {
+        self.gl
+            .DrawTransformFeedback(mode.into() as u32, transform_feedback_name.to_u32());
+    }
+
+    #[inline]
+    pub unsafe fn draw_transform_feedback_instanced<M>(
+        &self,
+        mode: M,
+        transform_feedback_name: TransformFeedbackName,
+        instance_count: u32,
+    ) where
+        M: Into<DrawMode>,
+    {
+        self.gl.DrawTransformFeedbackInstanced(
+            mode.into() as u32,
+            transform_feedback_name.to_u32(),
+            instance_count as i32,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn draw_transform_feedback_stream<M>(
+        &self,
+        mode: M,
+        transform_feedback_name: TransformFeedbackName,
+        stream: u32,
+    ) where
+        M: Into<DrawMode>,
+    {
+        self.gl.DrawTransformFeedbackStream(
+            mode.into() as u32,
+            transform_feedback_name.to_u32(),
+            stream,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn draw_transform_feedback_stream_instanced<M>(
+        &self,
+        mode: M,
+        transform_feedback_name: TransformFeedbackName,
+        stream: u32,
+        instance_count: u32,
+    ) where
+        M: Into<DrawMode>,
+    {
+        self.gl.DrawTransformFeedbackStreamInstanced(
+            mode.into() as u32,
+            transform_feedback_name.to_u32(),
+            stream,
+            instance_count as i32,
+        );
+    }
+
     // Samplers.
 
     #[inline]
@@ -2041,6 +3355,22 @@ impl Gl {
         self.gl.EndQuery(target.into() as u32);
     }
 
+    #[inline]
+    pub unsafe fn begin_query_indexed(
+        &self,
+        target: impl Into<ScopeQueryTarget>,
+        index: u32,
+        query_name: impl AsRef<QueryName>,
+    ) {
+        self.gl
+            .BeginQueryIndexed(target.into() as u32, index, query_name.as_ref().to_u32());
+    }
+
+    #[inline]
+    pub unsafe fn end_query_indexed(&self, target: impl Into<ScopeQueryTarget>, index: u32) {
+        self.gl.EndQueryIndexed(target.into() as u32, index);
+    }
+
     #[inline]
     pub unsafe fn query_counter(&self, query_name: impl AsRef<QueryName>) {
         self.gl
@@ -2061,17 +3391,38 @@ impl Gl {
 
     /// Non-blocking.
     #[inline]
-    pub unsafe fn try_query_result_u64(
+    pub unsafe fn try_query_result_u64(&self, query_name: impl AsRef<QueryName>) -> Option<u64> {
+        if self.query_result_available(&query_name) {
+            Some(self.query_result_u64(query_name))
+        } else {
+            None
+        }
+    }
+
+    /// Blocking. The type of the result is determined by the query target.
+    #[inline]
+    pub unsafe fn query_result<T>(&self, query_name: impl AsRef<QueryName>, _target: T) -> T::Value
+    where
+        T: query_target_param::Variant,
+    {
+        T::Value::get_query_object(&self.gl, query_name.as_ref().to_u32(), gl::QUERY_RESULT)
+    }
+
+    /// Non-blocking. The type of the result is determined by the query target.
+    #[inline]
+    pub unsafe fn try_query_result<T>(
         &self,
         query_name: impl AsRef<QueryName>,
-    ) -> Option<NonZeroU64> {
-        let mut value = 0u64;
-        self.gl.GetQueryObjectui64v(
-            query_name.as_ref().to_u32(),
-            gl::QUERY_RESULT_NO_WAIT,
-            &mut value,
-        );
-        NonZeroU64::new(value)
+        target: T,
+    ) -> Option<T::Value>
+    where
+        T: query_target_param::Variant,
+    {
+        if self.query_result_available(&query_name) {
+            Some(self.query_result(query_name, target))
+        } else {
+            None
+        }
     }
 
     #[inline]
@@ -2085,6 +3436,69 @@ impl Gl {
         value.assume_init() != 0
     }
 
+    #[inline]
+    pub unsafe fn get_query_buffer_objectiv(
+        &self,
+        query_name: impl AsRef<QueryName>,
+        buffer_name: impl AsRef<BufferName>,
+        param: impl Into<QueryObjectParam>,
+        byte_offset: usize,
+    ) {
+        self.gl.GetQueryBufferObjectiv(
+            query_name.as_ref().to_u32(),
+            buffer_name.as_ref().to_u32(),
+            param.into() as u32,
+            byte_offset as isize,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn get_query_buffer_objectuiv(
+        &self,
+        query_name: impl AsRef<QueryName>,
+        buffer_name: impl AsRef<BufferName>,
+        param: impl Into<QueryObjectParam>,
+        byte_offset: usize,
+    ) {
+        self.gl.GetQueryBufferObjectuiv(
+            query_name.as_ref().to_u32(),
+            buffer_name.as_ref().to_u32(),
+            param.into() as u32,
+            byte_offset as isize,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn get_query_buffer_objecti64v(
+        &self,
+        query_name: impl AsRef<QueryName>,
+        buffer_name: impl AsRef<BufferName>,
+        param: impl Into<QueryObjectParam>,
+        byte_offset: usize,
+    ) {
+        self.gl.GetQueryBufferObjecti64v(
+            query_name.as_ref().to_u32(),
+            buffer_name.as_ref().to_u32(),
+            param.into() as u32,
+            byte_offset as isize,
+        );
+    }
+
+    #[inline]
+    pub unsafe fn get_query_buffer_objectui64v(
+        &self,
+        query_name: impl AsRef<QueryName>,
+        buffer_name: impl AsRef<BufferName>,
+        param: impl Into<QueryObjectParam>,
+        byte_offset: usize,
+    ) {
+        self.gl.GetQueryBufferObjectui64v(
+            query_name.as_ref().to_u32(),
+            buffer_name.as_ref().to_u32(),
+            param.into() as u32,
+            byte_offset as isize,
+        );
+    }
 
     // Sync objects
 
@@ -2106,4 +3520,48 @@ impl Gl {
     pub unsafe fn wait_sync(&self, name: SyncName) {
         self.gl.WaitSync(name.to_gl(), 0, gl::TIMEOUT_IGNORED)
     }
+
+    /// Blocks for at most `timeout`. Timeouts that do not fit in 64 bits of
+    /// nanoseconds are clamped.
+    #[inline]
+    pub unsafe fn client_wait_sync(
+        &self,
+        name: SyncName,
+        flags: SyncFlags,
+        timeout: Duration,
+    ) -> SyncWaitResult {
+        self.gl
+            .ClientWaitSync(
+                name.to_gl(),
+                flags.bits(),
+                u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX),
+            )
+            .try_into()
+            .unwrap()
+    }
+
+    #[inline]
+    pub unsafe fn get_synciv<P>(&self, name: SyncName, _param: P) -> P::Value
+    where
+        P: get_synciv_param::Variant,
+        <P::Value as TryFrom<P::Intermediate>>::Error: std::fmt::Debug,
+    {
+        let mut value = MaybeUninit::<i32>::uninit();
+        self.gl.GetSynciv(
+            name.to_gl(),
+            P::VALUE,
+            1,
+            std::ptr::null_mut(),
+            value.as_mut_ptr(),
+        );
+        P::Intermediate::cast_from(value.assume_init())
+            .try_into()
+            .unwrap()
+    }
+
+    /// Non-blocking.
+    #[inline]
+    pub unsafe fn get_sync_status(&self, name: SyncName) -> SyncStatus {
+        self.get_synciv(name, SYNC_STATUS)
+    }
 }
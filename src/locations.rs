@@ -200,6 +200,16 @@ impl OptionUniformBlockIndex {
     }
 }
 
+impl_option_type! {
+    ShaderStorageBlockIndex,
+    OptionShaderStorageBlockIndex,
+    u32,
+    from_u32,
+    from_u32_unchecked,
+    to_u32,
+    u32::MAX,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct VertexArrayBufferBindingIndex(u32);
 
@@ -1,20 +1,16 @@
-use crate::gl;
-
-#[repr(u8)]
-pub enum MajorAxis {
-    Col = gl::FALSE,
-    Row = gl::TRUE,
-}
+use crate::MajorAxis;
 
 pub trait Major<T>: AsRef<T> {
     fn major_axis() -> MajorAxis;
 }
 
+#[derive(Debug, Copy, Clone, PartialEq)]
 #[repr(transparent)]
-pub struct RowMajorMatrix<T>(T);
+pub struct RowMajorMatrix<T>(pub T);
 
+#[derive(Debug, Copy, Clone, PartialEq)]
 #[repr(transparent)]
-pub struct ColMajorMatrix<T>(T);
+pub struct ColMajorMatrix<T>(pub T);
 
 macro_rules! impl_matrices {
     ($(($M:ident, $Flat:ty, $Row:ty, $Col:ty)),+ $(,)*) => {
@@ -65,7 +61,7 @@ macro_rules! impl_matrices {
             impl $M for ColMajorMatrix<$Col> {
                 #[inline]
                 fn major_axis() -> MajorAxis {
-                    MajorAxis::Col
+                    MajorAxis::Column
                 }
             }
         )+
@@ -0,0 +1,456 @@
+//! CPU side encoders for the packed and normalized vertex attribute formats.
+//! The conversions follow section 2.3.4 and 2.3.5 of the OpenGL 4.5 core
+//! specification.
+
+use crate::*;
+
+/// Rounds `value >> shift` to the nearest integer, ties to even.
+#[inline]
+fn shift_round_even(value: u32, shift: u32) -> u32 {
+    if shift == 0 {
+        return value;
+    }
+    if shift >= 32 {
+        return 0;
+    }
+    let truncated = value >> shift;
+    let remainder = value & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+    if remainder > half || (remainder == half && truncated & 1 == 1) {
+        truncated + 1
+    } else {
+        truncated
+    }
+}
+
+/// Encodes the magnitude of `value` as a float with a 5 bit exponent and
+/// `mantissa_bits` bits of mantissa. Finite values that are too large become
+/// infinity.
+#[inline]
+fn encode_float_magnitude(value: f32, mantissa_bits: u32) -> u32 {
+    let bits = value.to_bits() & 0x7fff_ffff;
+    let infinity = 0x1f << mantissa_bits;
+    if bits > 0x7f80_0000 {
+        return infinity | (1 << (mantissa_bits - 1));
+    }
+    let exponent = (bits >> 23) as i32 - 127 + 15;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent >= 31 {
+        return infinity;
+    }
+    if exponent <= 0 {
+        // Denormalized, may round up to the smallest normalized value.
+        let shift = (23 - mantissa_bits) as i32 + 1 - exponent;
+        return shift_round_even(mantissa | 0x80_0000, shift as u32);
+    }
+    // Rounding the mantissa up may carry into the exponent.
+    let encoded =
+        ((exponent as u32) << mantissa_bits) + shift_round_even(mantissa, 23 - mantissa_bits);
+    std::cmp::min(encoded, infinity)
+}
+
+#[inline]
+fn decode_float_magnitude(bits: u32, mantissa_bits: u32) -> f32 {
+    let exponent = bits >> mantissa_bits;
+    let mantissa = bits & ((1 << mantissa_bits) - 1);
+    match exponent {
+        0 => mantissa as f32 * f32::from_bits((127 - 14 - mantissa_bits) << 23),
+        0x1f => {
+            if mantissa == 0 {
+                f32::INFINITY
+            } else {
+                f32::NAN
+            }
+        }
+        _ => f32::from_bits(((exponent + 127 - 15) << 23) | (mantissa << (23 - mantissa_bits))),
+    }
+}
+
+/// Negative values become zero and finite values that are too large become
+/// the largest finite value.
+#[inline]
+fn encode_unsigned_float(value: f32, mantissa_bits: u32) -> u32 {
+    if value.is_nan() {
+        encode_float_magnitude(value, mantissa_bits)
+    } else if value <= 0.0 {
+        0
+    } else if value.is_infinite() {
+        0x1f << mantissa_bits
+    } else {
+        let max_finite = (0x1f << mantissa_bits) - 1;
+        std::cmp::min(encode_float_magnitude(value, mantissa_bits), max_finite)
+    }
+}
+
+#[inline]
+fn encode_unorm(value: f32, bits: u32) -> u32 {
+    let max = ((1u64 << bits) - 1) as f32;
+    if value.is_nan() {
+        return 0;
+    }
+    (value.clamp(0.0, 1.0) * max).round() as u32
+}
+
+#[inline]
+fn decode_unorm(value: u32, bits: u32) -> f32 {
+    value as f32 / ((1u64 << bits) - 1) as f32
+}
+
+#[inline]
+fn encode_snorm(value: f32, bits: u32) -> i32 {
+    let max = ((1u64 << (bits - 1)) - 1) as f32;
+    if value.is_nan() {
+        return 0;
+    }
+    (value.clamp(-1.0, 1.0) * max).round() as i32
+}
+
+#[inline]
+fn decode_snorm(value: i32, bits: u32) -> f32 {
+    (value as f32 / ((1u64 << (bits - 1)) - 1) as f32).max(-1.0)
+}
+
+/// Sign extends the lowest `bits` bits of `value`.
+#[inline]
+fn sign_extend(value: u32, bits: u32) -> i32 {
+    ((value << (32 - bits)) as i32) >> (32 - bits)
+}
+
+/// A 16-bit floating point number.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[repr(transparent)]
+pub struct Half(pub u16);
+
+impl Half {
+    pub const FORMAT: AttributeFormatF = AttributeFormatF::F16(ComponentCount::P1);
+
+    /// Rounds to the nearest representable value, ties to even. Values that
+    /// are too large become infinity.
+    #[inline]
+    pub fn encode(value: f32) -> Self {
+        let sign = (value.to_bits() >> 16) & 0x8000;
+        Half((sign | encode_float_magnitude(value, 10)) as u16)
+    }
+
+    #[inline]
+    pub fn decode(self) -> f32 {
+        let magnitude = decode_float_magnitude(u32::from(self.0) & 0x7fff, 10);
+        if self.0 & 0x8000 == 0 {
+            magnitude
+        } else {
+            -magnitude
+        }
+    }
+}
+
+/// Four signed normalized components packed as 10, 10, 10 and 2 bits.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[repr(transparent)]
+pub struct Snorm2_10_10_10(pub u32);
+
+impl Snorm2_10_10_10 {
+    pub const FORMAT: AttributeFormatF = AttributeFormatF::I_2_10_10_10_REV(true);
+
+    #[inline]
+    pub fn encode(value: [f32; 4]) -> Self {
+        let [x, y, z, w] = value;
+        Snorm2_10_10_10(
+            (encode_snorm(x, 10) as u32 & 0x3ff)
+                | (encode_snorm(y, 10) as u32 & 0x3ff) << 10
+                | (encode_snorm(z, 10) as u32 & 0x3ff) << 20
+                | (encode_snorm(w, 2) as u32 & 0x3) << 30,
+        )
+    }
+
+    #[inline]
+    pub fn decode(self) -> [f32; 4] {
+        [
+            decode_snorm(sign_extend(self.0, 10), 10),
+            decode_snorm(sign_extend(self.0 >> 10, 10), 10),
+            decode_snorm(sign_extend(self.0 >> 20, 10), 10),
+            decode_snorm(sign_extend(self.0 >> 30, 2), 2),
+        ]
+    }
+}
+
+/// Four unsigned normalized components packed as 10, 10, 10 and 2 bits.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[repr(transparent)]
+pub struct Unorm2_10_10_10(pub u32);
+
+impl Unorm2_10_10_10 {
+    pub const FORMAT: AttributeFormatF = AttributeFormatF::U_2_10_10_10_REV(true);
+
+    #[inline]
+    pub fn encode(value: [f32; 4]) -> Self {
+        let [x, y, z, w] = value;
+        Unorm2_10_10_10(
+            encode_unorm(x, 10)
+                | encode_unorm(y, 10) << 10
+                | encode_unorm(z, 10) << 20
+                | encode_unorm(w, 2) << 30,
+        )
+    }
+
+    #[inline]
+    pub fn decode(self) -> [f32; 4] {
+        [
+            decode_unorm(self.0 & 0x3ff, 10),
+            decode_unorm((self.0 >> 10) & 0x3ff, 10),
+            decode_unorm((self.0 >> 20) & 0x3ff, 10),
+            decode_unorm(self.0 >> 30, 2),
+        ]
+    }
+}
+
+/// Three unsigned floats packed as 11, 11 and 10 bits.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[repr(transparent)]
+pub struct R11G11B10F(pub u32);
+
+impl R11G11B10F {
+    pub const FORMAT: AttributeFormatF = AttributeFormatF::U_10F_11F_11F_REV;
+
+    /// Negative values become zero and finite values that are too large
+    /// become the largest finite value.
+    #[inline]
+    pub fn encode(value: [f32; 3]) -> Self {
+        let [r, g, b] = value;
+        R11G11B10F(
+            encode_unsigned_float(r, 6)
+                | encode_unsigned_float(g, 6) << 11
+                | encode_unsigned_float(b, 5) << 22,
+        )
+    }
+
+    #[inline]
+    pub fn decode(self) -> [f32; 3] {
+        [
+            decode_float_magnitude(self.0 & 0x7ff, 6),
+            decode_float_magnitude((self.0 >> 11) & 0x7ff, 6),
+            decode_float_magnitude(self.0 >> 22, 5),
+        ]
+    }
+}
+
+/// Four signed normalized 8-bit components.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[repr(transparent)]
+pub struct Snorm8x4(pub [i8; 4]);
+
+impl Snorm8x4 {
+    pub const FORMAT: AttributeFormatF = AttributeFormatF::I8(ComponentCount::P4, true);
+
+    #[inline]
+    pub fn encode(value: [f32; 4]) -> Self {
+        let mut result = [0; 4];
+        for (r, &v) in result.iter_mut().zip(value.iter()) {
+            *r = encode_snorm(v, 8) as i8;
+        }
+        Snorm8x4(result)
+    }
+
+    #[inline]
+    pub fn decode(self) -> [f32; 4] {
+        let mut result = [0.0; 4];
+        for (r, &v) in result.iter_mut().zip(self.0.iter()) {
+            *r = decode_snorm(i32::from(v), 8);
+        }
+        result
+    }
+}
+
+/// Two unsigned normalized 16-bit components.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+#[repr(transparent)]
+pub struct Unorm16x2(pub [u16; 2]);
+
+impl Unorm16x2 {
+    pub const FORMAT: AttributeFormatF = AttributeFormatF::U16(ComponentCount::P2, true);
+
+    #[inline]
+    pub fn encode(value: [f32; 2]) -> Self {
+        Unorm16x2([
+            encode_unorm(value[0], 16) as u16,
+            encode_unorm(value[1], 16) as u16,
+        ])
+    }
+
+    #[inline]
+    pub fn decode(self) -> [f32; 2] {
+        [
+            decode_unorm(u32::from(self.0[0]), 16),
+            decode_unorm(u32::from(self.0[1]), 16),
+        ]
+    }
+}
+
+macro_rules! impl_packed_vertex_attribute_types {
+    ($($Packed:ident,)*) => {
+        $(
+            impl VertexAttributeType for $Packed {
+                const FORMAT: AttributeFormat = AttributeFormat::F($Packed::FORMAT);
+            }
+        )*
+    };
+}
+
+impl_packed_vertex_attribute_types! {
+    Half,
+    Snorm2_10_10_10,
+    Unorm2_10_10_10,
+    R11G11B10F,
+    Snorm8x4,
+    Unorm16x2,
+}
+
+impl VertexAttributeType for [Half; 2] {
+    const FORMAT: AttributeFormat = AttributeFormat::F(AttributeFormatF::F16(ComponentCount::P2));
+}
+
+impl VertexAttributeType for [Half; 3] {
+    const FORMAT: AttributeFormat = AttributeFormat::F(AttributeFormatF::F16(ComponentCount::P3));
+}
+
+impl VertexAttributeType for [Half; 4] {
+    const FORMAT: AttributeFormat = AttributeFormat::F(AttributeFormatF::F16(ComponentCount::P4));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_round_trips_every_value() {
+        for bits in 0..=0xffffu16 {
+            let value = Half(bits).decode();
+            if value.is_nan() {
+                assert!(Half::encode(value).decode().is_nan());
+            } else {
+                assert_eq!(bits, Half::encode(value).0, "{:#06x} {}", bits, value);
+            }
+        }
+    }
+
+    #[test]
+    fn half_encodes_special_values() {
+        assert_eq!(0x3c00, Half::encode(1.0).0);
+        assert_eq!(0xc000, Half::encode(-2.0).0);
+        assert_eq!(0x7bff, Half::encode(65504.0).0);
+        assert_eq!(0x7c00, Half::encode(65520.0).0);
+        assert_eq!(0x7c00, Half::encode(f32::INFINITY).0);
+        assert_eq!(0xfc00, Half::encode(f32::NEG_INFINITY).0);
+        assert_eq!(0x8000, Half::encode(-0.0).0);
+        assert_eq!(0x0001, Half::encode(2.0f32.powi(-24)).0);
+        assert_eq!(0x0000, Half::encode(2.0f32.powi(-25)).0);
+        assert_eq!(0x0002, Half::encode(1.5 * 2.0f32.powi(-24)).0);
+        assert_eq!(0x0400, Half::encode(2.0f32.powi(-14)).0);
+        // Ties round to even.
+        assert_eq!(0x3c00, Half::encode(1.0 + 2.0f32.powi(-11)).0);
+        assert_eq!(0x3c02, Half::encode(1.0 + 3.0 * 2.0f32.powi(-11)).0);
+        assert!(Half::encode(f32::NAN).decode().is_nan());
+    }
+
+    #[test]
+    fn r11g11b10f_round_trips_every_value() {
+        for bits in 0..0x800 {
+            let r = R11G11B10F(bits).decode()[0];
+            let g = R11G11B10F(bits << 11).decode()[1];
+            if r.is_nan() {
+                assert!(R11G11B10F::encode([r, 0.0, 0.0]).decode()[0].is_nan());
+            } else {
+                assert_eq!(bits, R11G11B10F::encode([r, 0.0, 0.0]).0);
+                assert_eq!(bits << 11, R11G11B10F::encode([0.0, g, 0.0]).0);
+            }
+        }
+        for bits in 0..0x400 {
+            let b = R11G11B10F(bits << 22).decode()[2];
+            if !b.is_nan() {
+                assert_eq!(bits << 22, R11G11B10F::encode([0.0, 0.0, b]).0);
+            }
+        }
+    }
+
+    #[test]
+    fn r11g11b10f_clamps() {
+        assert_eq!(
+            [0.0, 0.0, 0.0],
+            R11G11B10F::encode([-1.0, -0.0, f32::NEG_INFINITY]).decode()
+        );
+        assert_eq!(
+            [65024.0, 65024.0, 64512.0],
+            R11G11B10F::encode([1.0e6, 65535.0, 1.0e6]).decode()
+        );
+        let inf = f32::INFINITY;
+        assert_eq!(
+            [inf, inf, inf],
+            R11G11B10F::encode([inf, inf, inf]).decode()
+        );
+    }
+
+    #[test]
+    fn snorm2_10_10_10() {
+        let packed = Snorm2_10_10_10::encode([1.0, -1.0, 0.0, -1.0]);
+        assert_eq!(0x1ff | 0x201 << 10 | 0x3 << 30, packed.0);
+        assert_eq!([1.0, -1.0, 0.0, -1.0], packed.decode());
+        // The most negative values decode to -1.
+        assert_eq!(
+            [-1.0, 0.0, 0.0, -1.0],
+            Snorm2_10_10_10(0x200 | 0x2 << 30).decode()
+        );
+        // Out of range values are clamped.
+        assert_eq!(
+            Snorm2_10_10_10::encode([1.0, -1.0, 0.5, 1.0]),
+            Snorm2_10_10_10::encode([2.0, -2.0, 0.5, 0.7])
+        );
+        assert_eq!(256, Snorm2_10_10_10::encode([0.5, 0.0, 0.0, 0.0]).0);
+    }
+
+    #[test]
+    fn unorm2_10_10_10() {
+        let packed = Unorm2_10_10_10::encode([1.0, 0.0, 0.5, 1.0]);
+        assert_eq!(0x3ff | 0x200 << 20 | 0x3 << 30, packed.0);
+        assert_eq!([1.0, 0.0, 512.0 / 1023.0, 1.0], packed.decode());
+        assert_eq!(
+            Unorm2_10_10_10::encode([1.0, 0.0, 0.0, 1.0 / 3.0]),
+            Unorm2_10_10_10::encode([2.0, -1.0, f32::NAN, 0.4])
+        );
+    }
+
+    #[test]
+    fn snorm8x4() {
+        assert_eq!(
+            Snorm8x4([127, -127, 0, 64]),
+            Snorm8x4::encode([1.0, -1.0, 0.0, 0.5])
+        );
+        assert_eq!(
+            [-1.0, -1.0, 0.0, 1.0],
+            Snorm8x4([-128, -127, 0, 127]).decode()
+        );
+        for v in -128..=127i8 {
+            let decoded = Snorm8x4([v, 0, 0, 0]).decode();
+            assert_eq!(std::cmp::max(v, -127), Snorm8x4::encode(decoded).0[0]);
+        }
+    }
+
+    #[test]
+    fn unorm16x2() {
+        assert_eq!(Unorm16x2([65535, 0]), Unorm16x2::encode([1.0, 0.0]));
+        assert_eq!(Unorm16x2([32768, 65535]), Unorm16x2::encode([0.5, 3.0]));
+        for v in 0..=65535u16 {
+            let decoded = Unorm16x2([v, v]).decode();
+            assert_eq!(Unorm16x2([v, v]), Unorm16x2::encode(decoded));
+        }
+    }
+
+    #[test]
+    fn formats_fit() {
+        assert_eq!(2, Half::FORMAT.byte_size() as usize);
+        assert_eq!(4, std::mem::size_of::<Snorm2_10_10_10>());
+        assert_eq!(4, Snorm2_10_10_10::FORMAT.byte_size());
+        assert_eq!(4, Unorm2_10_10_10::FORMAT.byte_size());
+        assert_eq!(4, R11G11B10F::FORMAT.byte_size());
+        assert_eq!(4, Snorm8x4::FORMAT.byte_size());
+        assert_eq!(4, Unorm16x2::FORMAT.byte_size());
+    }
+}
@@ -60,7 +60,7 @@ impl_param_read_single! {
 
 impl_param_read_single! {
     mod get_programiv_param(i32) {
-// ACTIVE_ATOMIC_COUNTER_BUFFERS,
+        ACTIVE_ATOMIC_COUNTER_BUFFERS(u32 => u32),
 // ACTIVE_ATTRIBUTE_MAX_LENGTH,
 // ACTIVE_ATTRIBUTES,
 // ACTIVE_UNIFORM_BLOCK_MAX_NAME_LENGTH,
@@ -76,7 +76,8 @@ impl_param_read_single! {
 // GEOMETRY_VERTICES_OUT,
         LINK_STATUS(i32 => LinkStatus),
         INFO_LOG_LENGTH(u32 => usize),
-// PROGRAM_BINARY_RETRIEVABLE_HINT,
+        PROGRAM_BINARY_LENGTH(u32 => usize),
+        PROGRAM_BINARY_RETRIEVABLE_HINT(bool => bool),
 // PROGRAM_SEPARABLE,
 // TESS_CONTROL_OUTPUT_VERTICES,
 // TESS_GEN_MODE,
@@ -89,12 +90,75 @@ impl_param_read_single! {
     }
 }
 
+impl_param_read_single! {
+    mod get_program_pipelineiv_param(i32) {
+        VALIDATE_STATUS(i32 => ValidateStatus),
+        INFO_LOG_LENGTH(u32 => usize),
+    }
+}
+
 impl_param_read_single! {
     mod get_integerv_param(i32) {
         MAX_COMPUTE_SHADER_STORAGE_BLOCKS(u32 => u32),
     }
 }
 
+impl_param_read_single! {
+    mod get_synciv_param(i32) {
+        OBJECT_TYPE(i32 => SyncObjectType),
+        SYNC_STATUS(i32 => SyncStatus),
+        SYNC_CONDITION(i32 => SyncCondition),
+    }
+}
+
+impl_param_read_single! {
+    mod get_vertex_array_iv_param(i32) {
+        ELEMENT_ARRAY_BUFFER_BINDING(u32 => u32),
+    }
+}
+
+impl_param_read_single! {
+    mod get_vertex_array_indexediv_param(i32) {
+        VERTEX_ATTRIB_ARRAY_ENABLED(bool => bool),
+        VERTEX_ATTRIB_ARRAY_SIZE(u32 => u32),
+        VERTEX_ATTRIB_ARRAY_STRIDE(u32 => u32),
+        VERTEX_ATTRIB_ARRAY_TYPE(u32 => u32),
+        VERTEX_ATTRIB_ARRAY_NORMALIZED(bool => bool),
+        VERTEX_ATTRIB_ARRAY_INTEGER(bool => bool),
+        VERTEX_ATTRIB_ARRAY_LONG(bool => bool),
+        VERTEX_ATTRIB_ARRAY_DIVISOR(u32 => u32),
+        VERTEX_ATTRIB_RELATIVE_OFFSET(u32 => u32),
+    }
+}
+
+impl_param_read_single! {
+    mod get_vertex_array_indexed64iv_param(i64) {
+        VERTEX_BINDING_OFFSET(u64 => usize),
+    }
+}
+
+impl_param_read_single! {
+    mod get_active_atomic_counter_bufferiv_param(i32) {
+        ATOMIC_COUNTER_BUFFER_BINDING(u32 => u32),
+        ATOMIC_COUNTER_BUFFER_DATA_SIZE(u32 => usize),
+        ATOMIC_COUNTER_BUFFER_ACTIVE_ATOMIC_COUNTERS(u32 => u32),
+        ATOMIC_COUNTER_BUFFER_REFERENCED_BY_VERTEX_SHADER(bool => bool),
+        ATOMIC_COUNTER_BUFFER_REFERENCED_BY_TESS_CONTROL_SHADER(bool => bool),
+        ATOMIC_COUNTER_BUFFER_REFERENCED_BY_TESS_EVALUATION_SHADER(bool => bool),
+        ATOMIC_COUNTER_BUFFER_REFERENCED_BY_GEOMETRY_SHADER(bool => bool),
+        ATOMIC_COUNTER_BUFFER_REFERENCED_BY_FRAGMENT_SHADER(bool => bool),
+        ATOMIC_COUNTER_BUFFER_REFERENCED_BY_COMPUTE_SHADER(bool => bool),
+    }
+}
+
+impl_param_read_single! {
+    mod get_program_interfaceiv_param(i32) {
+        ACTIVE_RESOURCES(u32 => u32),
+        MAX_NAME_LENGTH(u32 => usize),
+        MAX_NUM_ACTIVE_VARIABLES(u32 => u32),
+    }
+}
+
 impl_param_write_single! {
     mod tex_parameteri_param(i32) {
 // DEPTH_STENCIL_TEXTURE_MODE
@@ -108,6 +172,13 @@ impl_param_write_single! {
     }
 }
 
+impl_param_write_single! {
+    mod program_parameteri_param(i32) {
+        PROGRAM_BINARY_RETRIEVABLE_HINT(bool => i32),
+        PROGRAM_SEPARABLE(bool => i32),
+    }
+}
+
 impl_param_write_single! {
     mod tex_parameterf_param(f32) {
         TEXTURE_MAX_ANISOTROPY(f32 => f32),
@@ -126,3 +197,62 @@ impl_param_write_single! {
         TEXTURE_WRAP_R(TextureWrap => i32),
     }
 }
+
+/// Types that query object results can be read as.
+pub unsafe trait QueryResultValue: Sized {
+    #[doc(hidden)]
+    unsafe fn get_query_object(gl: &gl::Gl, name: u32, pname: u32) -> Self;
+}
+
+macro_rules! impl_query_result_values {
+    ($($Value:ty => $Raw:ty, $get:ident, |$v:ident| $convert:expr;)*) => {
+        $(
+            unsafe impl QueryResultValue for $Value {
+                #[inline]
+                unsafe fn get_query_object(gl: &gl::Gl, name: u32, pname: u32) -> Self {
+                    let mut value = std::mem::MaybeUninit::<$Raw>::uninit();
+                    gl.$get(name, pname, value.as_mut_ptr());
+                    let $v = value.assume_init();
+                    $convert
+                }
+            }
+        )*
+    };
+}
+
+impl_query_result_values! {
+    i32 => i32, GetQueryObjectiv, |v| v;
+    u32 => u32, GetQueryObjectuiv, |v| v;
+    i64 => i64, GetQueryObjecti64v, |v| v;
+    u64 => u64, GetQueryObjectui64v, |v| v;
+    bool => u32, GetQueryObjectuiv, |v| v != 0;
+}
+
+pub mod query_target_param {
+    use super::*;
+
+    /// Associates the type of a query's result with its target.
+    pub unsafe trait Variant: Symbol<u32> {
+        type Value: QueryResultValue;
+    }
+
+    macro_rules! impl_variants {
+        ($($Symbol:ident => $Value:ty,)*) => {
+            $(
+                unsafe impl Variant for $Symbol {
+                    type Value = $Value;
+                }
+            )*
+        };
+    }
+
+    impl_variants! {
+        SAMPLES_PASSED => u64,
+        ANY_SAMPLES_PASSED => bool,
+        ANY_SAMPLES_PASSED_CONSERVATIVE => bool,
+        TIME_ELAPSED => u64,
+        TIMESTAMP => u64,
+        PRIMITIVES_GENERATED => u32,
+        TRANSFORM_FEEDBACK_PRIMITIVES_WRITTEN => u32,
+    }
+}
@@ -0,0 +1,443 @@
+//! A GPU timer profiler built on `TIMESTAMP` queries.
+//!
+//! Scopes are recorded into a per-frame pool of queries. The results of a
+//! frame are only read back when its pool is about to be reused, `latency`
+//! frames later. If they are not available by then they are dropped instead of
+//! waiting for the GPU.
+
+use crate::*;
+
+/// The OpenGL functionality required by the `Profiler`.
+pub trait ProfilerBackend {
+    unsafe fn create_timestamp_query(&self) -> QueryName;
+
+    unsafe fn delete_query(&self, query_name: QueryName);
+
+    unsafe fn query_counter(&self, query_name: &QueryName);
+
+    unsafe fn query_result_available(&self, query_name: &QueryName) -> bool;
+
+    unsafe fn query_result_u64(&self, query_name: &QueryName) -> u64;
+
+    unsafe fn push_debug_group(&self, label: &str);
+
+    unsafe fn pop_debug_group(&self);
+}
+
+impl ProfilerBackend for Gl {
+    #[inline]
+    unsafe fn create_timestamp_query(&self) -> QueryName {
+        self.create_query(TIMESTAMP)
+    }
+
+    #[inline]
+    unsafe fn delete_query(&self, query_name: QueryName) {
+        Gl::delete_query(self, query_name);
+    }
+
+    #[inline]
+    unsafe fn query_counter(&self, query_name: &QueryName) {
+        Gl::query_counter(self, query_name);
+    }
+
+    #[inline]
+    unsafe fn query_result_available(&self, query_name: &QueryName) -> bool {
+        Gl::query_result_available(self, query_name)
+    }
+
+    #[inline]
+    unsafe fn query_result_u64(&self, query_name: &QueryName) -> u64 {
+        Gl::query_result_u64(self, query_name)
+    }
+
+    #[inline]
+    unsafe fn push_debug_group(&self, label: &str) {
+        Gl::push_debug_group(self, DEBUG_SOURCE_APPLICATION, 0, label);
+    }
+
+    #[inline]
+    unsafe fn pop_debug_group(&self) {
+        Gl::pop_debug_group(self);
+    }
+}
+
+/// The measured GPU time of a scope and its nested scopes.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProfileNode {
+    pub label: String,
+    pub gpu_ns: u64,
+    pub children: Vec<ProfileNode>,
+}
+
+/// The measured scopes of a single frame.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProfileFrame {
+    pub frame_index: u64,
+    pub roots: Vec<ProfileNode>,
+}
+
+#[derive(Debug)]
+struct Scope {
+    label: String,
+    parent: Option<usize>,
+    begin: usize,
+    end: usize,
+}
+
+#[derive(Debug, Default)]
+struct FrameQueries {
+    frame_index: u64,
+    pending: bool,
+    queries: Vec<QueryName>,
+    used: usize,
+    scopes: Vec<Scope>,
+    stack: Vec<usize>,
+}
+
+impl FrameQueries {
+    unsafe fn next_query<B: ProfilerBackend>(&mut self, backend: &B) -> usize {
+        if self.used == self.queries.len() {
+            self.queries.push(backend.create_timestamp_query());
+        }
+        let index = self.used;
+        self.used += 1;
+        index
+    }
+
+    unsafe fn try_collect<B: ProfilerBackend>(&self, backend: &B) -> Option<ProfileFrame> {
+        let queries = &self.queries[0..self.used];
+
+        if !queries
+            .iter()
+            .all(|query| backend.query_result_available(query))
+        {
+            return None;
+        }
+
+        let timestamps: Vec<u64> = queries
+            .iter()
+            .map(|query| backend.query_result_u64(query))
+            .collect();
+
+        // Scopes are stored in the order they began so children always come
+        // after their parent. Build the tree back to front.
+        let mut children: Vec<Vec<ProfileNode>> = self.scopes.iter().map(|_| Vec::new()).collect();
+        let mut roots = Vec::new();
+
+        for (index, scope) in self.scopes.iter().enumerate().rev() {
+            let mut node_children = std::mem::take(&mut children[index]);
+            node_children.reverse();
+            let node = ProfileNode {
+                label: scope.label.clone(),
+                gpu_ns: timestamps[scope.end].saturating_sub(timestamps[scope.begin]),
+                children: node_children,
+            };
+            match scope.parent {
+                Some(parent) => children[parent].push(node),
+                None => roots.push(node),
+            }
+        }
+
+        roots.reverse();
+
+        Some(ProfileFrame {
+            frame_index: self.frame_index,
+            roots,
+        })
+    }
+}
+
+/// Records nested, named scopes with `TIMESTAMP` queries.
+///
+/// ```ignore
+/// profiler.begin_frame(&gl);
+/// profiler.begin_scope(&gl, "shadows");
+/// // ...
+/// profiler.end_scope(&gl);
+/// profiler.end_frame(&gl);
+///
+/// if let Some(frame) = profiler.results() {
+///     // ...
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Profiler {
+    frames: Vec<FrameQueries>,
+    frame_index: u64,
+    recording: bool,
+    latest: Option<ProfileFrame>,
+    dropped_frames: u64,
+}
+
+impl Profiler {
+    /// Results of a frame are read back `latency` frames after it ended.
+    pub fn new(latency: usize) -> Self {
+        Profiler {
+            frames: (0..=latency).map(|_| FrameQueries::default()).collect(),
+            frame_index: 0,
+            recording: false,
+            latest: None,
+            dropped_frames: 0,
+        }
+    }
+
+    #[inline]
+    fn current_frame(&mut self) -> &mut FrameQueries {
+        assert!(self.recording, "Not recording a frame.");
+        let index = (self.frame_index % self.frames.len() as u64) as usize;
+        &mut self.frames[index]
+    }
+
+    pub unsafe fn begin_frame<B: ProfilerBackend>(&mut self, backend: &B) {
+        assert!(
+            !self.recording,
+            "Called begin_frame twice without end_frame."
+        );
+        self.recording = true;
+
+        let frame_index = self.frame_index;
+        let frame = self.current_frame();
+
+        let collected = if frame.pending {
+            Some(frame.try_collect(backend))
+        } else {
+            None
+        };
+
+        frame.frame_index = frame_index;
+        frame.pending = false;
+        frame.used = 0;
+        frame.scopes.clear();
+        frame.stack.clear();
+
+        match collected {
+            Some(Some(results)) => self.latest = Some(results),
+            Some(None) => self.dropped_frames += 1,
+            None => {}
+        }
+    }
+
+    pub unsafe fn end_frame<B: ProfilerBackend>(&mut self, _backend: &B) {
+        let frame = self.current_frame();
+        assert!(
+            frame.stack.is_empty(),
+            "Called end_frame with {} unterminated scope(s).",
+            frame.stack.len()
+        );
+        frame.pending = !frame.scopes.is_empty();
+        self.recording = false;
+        self.frame_index += 1;
+    }
+
+    pub unsafe fn begin_scope<B: ProfilerBackend>(&mut self, backend: &B, label: &str) {
+        let frame = self.current_frame();
+        backend.push_debug_group(label);
+        let begin = frame.next_query(backend);
+        backend.query_counter(&frame.queries[begin]);
+        let index = frame.scopes.len();
+        frame.scopes.push(Scope {
+            label: String::from(label),
+            parent: frame.stack.last().cloned(),
+            begin,
+            end: begin,
+        });
+        frame.stack.push(index);
+    }
+
+    pub unsafe fn end_scope<B: ProfilerBackend>(&mut self, backend: &B) {
+        let frame = self.current_frame();
+        let index = frame
+            .stack
+            .pop()
+            .expect("Called end_scope without a matching begin_scope.");
+        let end = frame.next_query(backend);
+        backend.query_counter(&frame.queries[end]);
+        frame.scopes[index].end = end;
+        backend.pop_debug_group();
+    }
+
+    /// The most recent frame whose results have been read back.
+    #[inline]
+    pub fn results(&self) -> Option<&ProfileFrame> {
+        self.latest.as_ref()
+    }
+
+    /// The number of frames whose results were not available in time.
+    #[inline]
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped_frames
+    }
+
+    pub unsafe fn delete<B: ProfilerBackend>(self, backend: &B) {
+        for frame in self.frames {
+            for query in frame.queries {
+                backend.delete_query(query);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+
+    /// Simulates a GPU that finishes work `delay` calls to `advance` after it
+    /// was submitted and whose clock ticks 10ns every `query_counter`.
+    #[derive(Default)]
+    struct MockBackend {
+        next_name: Cell<u32>,
+        clock: Cell<u64>,
+        now: Cell<u64>,
+        delay: Cell<u64>,
+        // name -> (timestamp, submitted at)
+        results: RefCell<HashMap<u32, (u64, u64)>>,
+        debug_groups: RefCell<Vec<String>>,
+        debug_depth: Cell<usize>,
+    }
+
+    impl MockBackend {
+        fn advance(&self) {
+            self.now.set(self.now.get() + 1);
+        }
+    }
+
+    impl ProfilerBackend for MockBackend {
+        unsafe fn create_timestamp_query(&self) -> QueryName {
+            self.next_name.set(self.next_name.get() + 1);
+            QueryName::new(self.next_name.get()).unwrap()
+        }
+
+        unsafe fn delete_query(&self, query_name: QueryName) {
+            self.results.borrow_mut().remove(&query_name.to_u32());
+        }
+
+        unsafe fn query_counter(&self, query_name: &QueryName) {
+            self.clock.set(self.clock.get() + 10);
+            self.results
+                .borrow_mut()
+                .insert(query_name.to_u32(), (self.clock.get(), self.now.get()));
+        }
+
+        unsafe fn query_result_available(&self, query_name: &QueryName) -> bool {
+            let (_, submitted) = self.results.borrow()[&query_name.to_u32()];
+            self.now.get() >= submitted + self.delay.get()
+        }
+
+        unsafe fn query_result_u64(&self, query_name: &QueryName) -> u64 {
+            assert!(self.query_result_available(query_name), "Would stall.");
+            self.results.borrow()[&query_name.to_u32()].0
+        }
+
+        unsafe fn push_debug_group(&self, label: &str) {
+            self.debug_groups.borrow_mut().push(String::from(label));
+            self.debug_depth.set(self.debug_depth.get() + 1);
+        }
+
+        unsafe fn pop_debug_group(&self) {
+            self.debug_depth.set(self.debug_depth.get() - 1);
+        }
+    }
+
+    unsafe fn record_frame(profiler: &mut Profiler, backend: &MockBackend) {
+        profiler.begin_frame(backend);
+        profiler.begin_scope(backend, "frame");
+        profiler.begin_scope(backend, "shadows");
+        profiler.end_scope(backend);
+        profiler.begin_scope(backend, "opaque");
+        profiler.begin_scope(backend, "terrain");
+        profiler.end_scope(backend);
+        profiler.end_scope(backend);
+        profiler.end_scope(backend);
+        profiler.end_frame(backend);
+        backend.advance();
+    }
+
+    #[test]
+    fn builds_nested_tree_after_latency() {
+        let backend = MockBackend::default();
+        backend.delay.set(2);
+        let mut profiler = Profiler::new(2);
+
+        unsafe {
+            for _ in 0..3 {
+                record_frame(&mut profiler, &backend);
+            }
+            assert_eq!(profiler.results(), None);
+            record_frame(&mut profiler, &backend);
+        }
+
+        let node = |label: &str, gpu_ns, children| ProfileNode {
+            label: String::from(label),
+            gpu_ns,
+            children,
+        };
+
+        assert_eq!(
+            profiler.results(),
+            Some(&ProfileFrame {
+                frame_index: 0,
+                roots: vec![node(
+                    "frame",
+                    70,
+                    vec![
+                        node("shadows", 10, vec![]),
+                        node("opaque", 30, vec![node("terrain", 10, vec![])]),
+                    ]
+                )],
+            })
+        );
+        assert_eq!(profiler.dropped_frames(), 0);
+        assert_eq!(backend.debug_depth.get(), 0);
+        assert_eq!(
+            &backend.debug_groups.borrow()[0..4],
+            &["frame", "shadows", "opaque", "terrain"]
+        );
+    }
+
+    #[test]
+    fn reuses_query_pool() {
+        let backend = MockBackend::default();
+        let mut profiler = Profiler::new(1);
+
+        unsafe {
+            for _ in 0..10 {
+                record_frame(&mut profiler, &backend);
+            }
+        }
+
+        // 2 frames in flight, 8 queries per frame.
+        assert_eq!(backend.next_name.get(), 16);
+        assert_eq!(profiler.results().unwrap().frame_index, 7);
+    }
+
+    #[test]
+    fn drops_unavailable_results_instead_of_stalling() {
+        let backend = MockBackend::default();
+        backend.delay.set(5);
+        let mut profiler = Profiler::new(1);
+
+        unsafe {
+            for _ in 0..4 {
+                record_frame(&mut profiler, &backend);
+            }
+        }
+
+        assert_eq!(profiler.results(), None);
+        assert_eq!(profiler.dropped_frames(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn unbalanced_scopes_panic() {
+        let backend = MockBackend::default();
+        let mut profiler = Profiler::new(1);
+
+        unsafe {
+            profiler.begin_frame(&backend);
+            profiler.begin_scope(&backend, "frame");
+            profiler.end_frame(&backend);
+        }
+    }
+}
@@ -0,0 +1,351 @@
+use crate::*;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// An implementation defined program binary format as returned by
+/// `get_program_binary`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[repr(transparent)]
+pub struct ProgramBinaryFormat(pub u32);
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ProgramBinary {
+    pub format: ProgramBinaryFormat,
+    pub data: Vec<u8>,
+}
+
+impl Gl {
+    /// Retrieves the binary of a linked program. Set
+    /// `PROGRAM_BINARY_RETRIEVABLE_HINT` before linking to make sure the
+    /// binary is available.
+    #[inline]
+    pub unsafe fn get_program_binary(&self, program_name: ProgramName) -> ProgramBinary {
+        let mut data = Vec::with_capacity(self.get_programiv(program_name, PROGRAM_BINARY_LENGTH));
+        let mut length = MaybeUninit::<i32>::uninit();
+        let mut format = MaybeUninit::<u32>::uninit();
+        self.gl.GetProgramBinary(
+            program_name.to_u32(),
+            data.capacity() as i32,
+            length.as_mut_ptr(),
+            format.as_mut_ptr(),
+            data.as_mut_ptr() as *mut c_void,
+        );
+        let length = length.assume_init();
+        assert!(length >= 0 && length <= data.capacity() as i32);
+        data.set_len(length as usize);
+        ProgramBinary {
+            format: ProgramBinaryFormat(format.assume_init()),
+            data,
+        }
+    }
+
+    /// Loads a program binary. Check `LINK_STATUS` afterwards, the driver
+    /// rejects binaries from other drivers or driver versions.
+    #[inline]
+    pub unsafe fn program_binary(
+        &self,
+        program_name: ProgramName,
+        format: ProgramBinaryFormat,
+        binary: &[u8],
+    ) {
+        self.gl.ProgramBinary(
+            program_name.to_u32(),
+            format.0,
+            binary.as_ptr() as *const c_void,
+            binary.len() as i32,
+        );
+    }
+}
+
+/// Identifies the driver that produced a program binary.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct DriverInfo {
+    pub vendor: &'static str,
+    pub renderer: &'static str,
+    pub version: &'static str,
+}
+
+impl Gl {
+    #[inline]
+    pub unsafe fn get_driver_info(&self) -> DriverInfo {
+        DriverInfo {
+            vendor: self.get_string(GetStringParam::Vendor),
+            renderer: self.get_string(GetStringParam::Renderer),
+            version: self.get_string(GetStringParam::Version),
+        }
+    }
+}
+
+const CACHE_MAGIC: &[u8; 8] = b"GLTYPBIN";
+const CACHE_VERSION: u32 = 1;
+const CACHE_HEADER_LENGTH: usize = 8 + 4 + 8 + 4 + 8;
+
+/// A 64-bit FNV-1a hash. Unlike `DefaultHasher` its output is stable across
+/// compiler versions, which matters for keys that are stored on disk.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    /// Prefixes the length so that `["ab", "c"]` and `["a", "bc"]` differ.
+    fn write_str(&mut self, s: &str) {
+        self.write(&(s.len() as u64).to_le_bytes());
+        self.write(s.as_bytes());
+    }
+}
+
+/// The hash of everything that determines a program binary.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ProgramCacheKey(pub u64);
+
+impl ProgramCacheKey {
+    /// Hashes the driver and the sources of every stage in order.
+    pub fn new(driver: &DriverInfo, sources: &[&str]) -> Self {
+        let mut hasher = Fnv1a::new();
+        hasher.write(&CACHE_VERSION.to_le_bytes());
+        hasher.write_str(driver.vendor);
+        hasher.write_str(driver.renderer);
+        hasher.write_str(driver.version);
+        hasher.write(&(sources.len() as u64).to_le_bytes());
+        for source in sources {
+            hasher.write_str(source);
+        }
+        ProgramCacheKey(hasher.0)
+    }
+}
+
+fn encode_entry(key: ProgramCacheKey, binary: &ProgramBinary) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(CACHE_HEADER_LENGTH + binary.data.len());
+    bytes.extend_from_slice(CACHE_MAGIC);
+    bytes.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&key.0.to_le_bytes());
+    bytes.extend_from_slice(&binary.format.0.to_le_bytes());
+    bytes.extend_from_slice(&(binary.data.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&binary.data);
+    bytes
+}
+
+/// Returns `None` for truncated, corrupted or outdated entries.
+fn decode_entry(key: ProgramCacheKey, bytes: &[u8]) -> Option<ProgramBinary> {
+    if bytes.len() < CACHE_HEADER_LENGTH || &bytes[0..8] != CACHE_MAGIC {
+        return None;
+    }
+    let mut u32_bytes = [0; 4];
+    let mut u64_bytes = [0; 8];
+
+    u32_bytes.copy_from_slice(&bytes[8..12]);
+    if u32::from_le_bytes(u32_bytes) != CACHE_VERSION {
+        return None;
+    }
+    u64_bytes.copy_from_slice(&bytes[12..20]);
+    if u64::from_le_bytes(u64_bytes) != key.0 {
+        return None;
+    }
+    u32_bytes.copy_from_slice(&bytes[20..24]);
+    let format = ProgramBinaryFormat(u32::from_le_bytes(u32_bytes));
+    u64_bytes.copy_from_slice(&bytes[24..32]);
+    let data = &bytes[CACHE_HEADER_LENGTH..];
+    if u64::from_le_bytes(u64_bytes) != data.len() as u64 {
+        return None;
+    }
+    Some(ProgramBinary {
+        format,
+        data: data.to_vec(),
+    })
+}
+
+/// Stores program binaries in a directory, one file per `ProgramCacheKey`.
+/// Changing a source or updating the driver changes the key so old entries
+/// are never loaded.
+#[derive(Debug, Clone)]
+pub struct ProgramCache {
+    pub directory: PathBuf,
+}
+
+impl ProgramCache {
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        ProgramCache {
+            directory: directory.into(),
+        }
+    }
+
+    pub fn entry_path(&self, key: ProgramCacheKey) -> PathBuf {
+        self.directory.join(format!("{:016x}.bin", key.0))
+    }
+
+    /// Returns `None` when there is no entry. Invalid entries are removed.
+    pub fn load(&self, key: ProgramCacheKey) -> io::Result<Option<ProgramBinary>> {
+        let path = self.entry_path(key);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error),
+        };
+        match decode_entry(key, &bytes) {
+            Some(binary) => Ok(Some(binary)),
+            None => {
+                remove_file(&path)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Writes to a temporary file first so a crash never leaves a truncated
+    /// entry behind.
+    pub fn store(&self, key: ProgramCacheKey, binary: &ProgramBinary) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        let path = self.entry_path(key);
+        let temporary_path = path.with_extension("tmp");
+        fs::write(&temporary_path, encode_entry(key, binary))?;
+        fs::rename(&temporary_path, &path)
+    }
+
+    pub fn remove(&self, key: ProgramCacheKey) -> io::Result<()> {
+        remove_file(&self.entry_path(key))
+    }
+}
+
+fn remove_file(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(ref error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+impl Gl {
+    /// Loads a program from `cache`. When there is no entry or the driver
+    /// rejects the binary, a new program is passed to `link` which should
+    /// attach the shaders and link it, after which its binary is stored.
+    ///
+    /// The cache is best effort: file system errors only cause a full
+    /// compile.
+    pub unsafe fn create_program_cached<F>(
+        &self,
+        cache: &ProgramCache,
+        key: ProgramCacheKey,
+        link: F,
+    ) -> Result<ProgramName, Vec<ShaderDiagnostic>>
+    where
+        F: FnOnce(ProgramName) -> Result<(), Vec<ShaderDiagnostic>>,
+    {
+        if let Ok(Some(binary)) = cache.load(key) {
+            let program_name = self.create_program();
+            self.program_binary(program_name, binary.format, &binary.data);
+            if let LinkStatus::Linked = self.get_programiv(program_name, LINK_STATUS) {
+                return Ok(program_name);
+            }
+            self.delete_program(program_name);
+            let _ = cache.remove(key);
+        }
+
+        let program_name = self.create_program();
+        self.program_parameteri(program_name, PROGRAM_BINARY_RETRIEVABLE_HINT, true);
+        if let Err(diagnostics) = link(program_name) {
+            self.delete_program(program_name);
+            return Err(diagnostics);
+        }
+        if self.get_programiv(program_name, PROGRAM_BINARY_LENGTH) > 0 {
+            let _ = cache.store(key, &self.get_program_binary(program_name));
+        }
+        Ok(program_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DRIVER: DriverInfo = DriverInfo {
+        vendor: "NVIDIA Corporation",
+        renderer: "GeForce GTX 1080/PCIe/SSE2",
+        version: "4.5.0 NVIDIA 418.56",
+    };
+
+    fn binary() -> ProgramBinary {
+        ProgramBinary {
+            format: ProgramBinaryFormat(0x8e21),
+            data: vec![1, 2, 3, 4, 5],
+        }
+    }
+
+    fn temporary_cache(name: &str) -> ProgramCache {
+        let directory =
+            std::env::temp_dir().join(format!("gl-typed-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        ProgramCache::new(directory)
+    }
+
+    #[test]
+    fn keys_depend_on_driver_and_sources() {
+        let key = ProgramCacheKey::new(&DRIVER, &["vs", "fs"]);
+        assert_eq!(key, ProgramCacheKey::new(&DRIVER, &["vs", "fs"]));
+        assert_ne!(key, ProgramCacheKey::new(&DRIVER, &["vsf", "s"]));
+        assert_ne!(key, ProgramCacheKey::new(&DRIVER, &["vs", "fs", ""]));
+        assert_ne!(
+            key,
+            ProgramCacheKey::new(
+                &DriverInfo {
+                    version: "4.5.0 NVIDIA 430.14",
+                    ..DRIVER
+                },
+                &["vs", "fs"]
+            )
+        );
+
+        // Keys must not change between builds.
+        let mut hasher = Fnv1a::new();
+        hasher.write(b"a");
+        assert_eq!(0xaf63_dc4c_8601_ec8c, hasher.0);
+    }
+
+    #[test]
+    fn decodes_only_valid_entries() {
+        let key = ProgramCacheKey(42);
+        let bytes = encode_entry(key, &binary());
+        assert_eq!(CACHE_HEADER_LENGTH + 5, bytes.len());
+        assert_eq!(Some(binary()), decode_entry(key, &bytes));
+
+        assert_eq!(None, decode_entry(ProgramCacheKey(43), &bytes));
+        assert_eq!(None, decode_entry(key, &bytes[..bytes.len() - 1]));
+        assert_eq!(None, decode_entry(key, &bytes[..CACHE_HEADER_LENGTH - 1]));
+        let mut outdated = bytes.clone();
+        outdated[8] += 1;
+        assert_eq!(None, decode_entry(key, &outdated));
+        let mut corrupted = bytes;
+        corrupted[0] = b'X';
+        assert_eq!(None, decode_entry(key, &corrupted));
+    }
+
+    #[test]
+    fn stores_loads_and_invalidates_entries() {
+        let cache = temporary_cache("program-cache");
+        let key = ProgramCacheKey::new(&DRIVER, &["vs", "fs"]);
+
+        assert_eq!(None, cache.load(key).unwrap());
+        cache.store(key, &binary()).unwrap();
+        assert_eq!(Some(binary()), cache.load(key).unwrap());
+
+        let other_key = ProgramCacheKey::new(&DRIVER, &["vs", "fs2"]);
+        assert_eq!(None, cache.load(other_key).unwrap());
+
+        fs::write(cache.entry_path(key), b"GLTYPBIN").unwrap();
+        assert_eq!(None, cache.load(key).unwrap());
+        assert!(!cache.entry_path(key).exists());
+
+        cache.store(key, &binary()).unwrap();
+        cache.remove(key).unwrap();
+        cache.remove(key).unwrap();
+        assert_eq!(None, cache.load(key).unwrap());
+
+        fs::remove_dir_all(&cache.directory).unwrap();
+    }
+}
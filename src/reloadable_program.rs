@@ -0,0 +1,491 @@
+use crate::*;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// The calls `ReloadableProgram` makes to build a program. Implemented by
+/// `Gl` and by mocks in tests.
+pub trait ProgramBuilder {
+    unsafe fn create_shader(&self, kind: ShaderKind) -> ShaderName;
+    unsafe fn shader_source(&self, shader_name: ShaderName, source: &str);
+    unsafe fn compile_shader_checked(
+        &self,
+        shader_name: ShaderName,
+    ) -> Result<(), Vec<ShaderDiagnostic>>;
+    unsafe fn delete_shader(&self, shader_name: ShaderName);
+    unsafe fn create_program(&self) -> ProgramName;
+    unsafe fn attach_shader(&self, program_name: ProgramName, shader_name: ShaderName);
+    unsafe fn link_program_checked(
+        &self,
+        program_name: ProgramName,
+    ) -> Result<(), Vec<ShaderDiagnostic>>;
+    unsafe fn delete_program(&self, program_name: ProgramName);
+}
+
+impl ProgramBuilder for Gl {
+    unsafe fn create_shader(&self, kind: ShaderKind) -> ShaderName {
+        Gl::create_shader(self, kind)
+    }
+
+    unsafe fn shader_source(&self, shader_name: ShaderName, source: &str) {
+        Gl::shader_source(self, shader_name, &[source])
+    }
+
+    unsafe fn compile_shader_checked(
+        &self,
+        shader_name: ShaderName,
+    ) -> Result<(), Vec<ShaderDiagnostic>> {
+        Gl::compile_shader_checked(self, shader_name)
+    }
+
+    unsafe fn delete_shader(&self, shader_name: ShaderName) {
+        Gl::delete_shader(self, shader_name)
+    }
+
+    unsafe fn create_program(&self) -> ProgramName {
+        Gl::create_program(self)
+    }
+
+    unsafe fn attach_shader(&self, program_name: ProgramName, shader_name: ShaderName) {
+        Gl::attach_shader(self, program_name, shader_name)
+    }
+
+    unsafe fn link_program_checked(
+        &self,
+        program_name: ProgramName,
+    ) -> Result<(), Vec<ShaderDiagnostic>> {
+        Gl::link_program_checked(self, program_name)
+    }
+
+    unsafe fn delete_program(&self, program_name: ProgramName) {
+        Gl::delete_program(self, program_name)
+    }
+}
+
+/// The file system access of `ReloadableProgram`.
+pub trait ShaderFileSystem {
+    fn modified(&self, path: &Path) -> io::Result<SystemTime>;
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct StdFileSystem;
+
+impl ShaderFileSystem for StdFileSystem {
+    fn modified(&self, path: &Path) -> io::Result<SystemTime> {
+        fs::metadata(path)?.modified()
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+}
+
+#[derive(Debug)]
+pub enum ReloadError {
+    Read {
+        path: PathBuf,
+        error: io::Error,
+    },
+    Compile {
+        path: PathBuf,
+        diagnostics: Vec<ShaderDiagnostic>,
+    },
+    Link {
+        diagnostics: Vec<ShaderDiagnostic>,
+    },
+}
+
+impl fmt::Display for ReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReloadError::Read { path, error } => {
+                write!(f, "Failed to read {}: {}", path.display(), error)
+            }
+            ReloadError::Compile { path, diagnostics } => {
+                write!(f, "Failed to compile {}:", path.display())?;
+                for diagnostic in diagnostics {
+                    write!(f, "\n{}", diagnostic)?;
+                }
+                Ok(())
+            }
+            ReloadError::Link { diagnostics } => {
+                write!(f, "Failed to link:")?;
+                for diagnostic in diagnostics {
+                    write!(f, "\n{}", diagnostic)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReloadError {}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ReloadStatus {
+    Unchanged,
+    Reloaded,
+}
+
+#[derive(Debug)]
+struct TrackedFile {
+    path: PathBuf,
+    /// `None` when the file could not be inspected, for example because an
+    /// editor replaced it.
+    modified: Option<SystemTime>,
+}
+
+/// A program that is rebuilt from its shader files when one of them changes.
+///
+/// Call `poll` every frame. The current program stays in use until a rebuild
+/// succeeds.
+#[derive(Debug)]
+pub struct ReloadableProgram {
+    stages: Vec<(ShaderKind, PathBuf)>,
+    tracked: Vec<TrackedFile>,
+    program_name: Option<ProgramName>,
+    built: bool,
+}
+
+impl ReloadableProgram {
+    pub fn new<I, P>(stages: I) -> Self
+    where
+        I: IntoIterator<Item = (ShaderKind, P)>,
+        P: Into<PathBuf>,
+    {
+        let stages: Vec<(ShaderKind, PathBuf)> = stages
+            .into_iter()
+            .map(|(kind, path)| (kind, path.into()))
+            .collect();
+        let tracked = stages
+            .iter()
+            .map(|(_, path)| TrackedFile {
+                path: path.clone(),
+                modified: None,
+            })
+            .collect();
+        ReloadableProgram {
+            stages,
+            tracked,
+            program_name: None,
+            built: false,
+        }
+    }
+
+    /// Also rebuild when `path` changes, for example an included file.
+    pub fn track<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.tracked.push(TrackedFile {
+            path: path.into(),
+            modified: None,
+        });
+        self
+    }
+
+    /// The most recent program that was built successfully.
+    pub fn program_name(&self) -> Option<ProgramName> {
+        self.program_name
+    }
+
+    /// Rebuilds the program on the first call and whenever the modification
+    /// time of a tracked file changes. A failed build is not retried until a
+    /// file changes again.
+    pub unsafe fn poll<B, F>(&mut self, builder: &B, fs: &F) -> Result<ReloadStatus, ReloadError>
+    where
+        B: ProgramBuilder,
+        F: ShaderFileSystem,
+    {
+        let mut changed = !self.built;
+        for file in self.tracked.iter_mut() {
+            let modified = fs.modified(&file.path).ok();
+            if modified != file.modified {
+                file.modified = modified;
+                changed = true;
+            }
+        }
+        if !changed {
+            return Ok(ReloadStatus::Unchanged);
+        }
+        self.built = true;
+        self.reload(builder, fs)
+    }
+
+    /// Rebuilds the program without checking modification times.
+    pub unsafe fn reload<B, F>(&mut self, builder: &B, fs: &F) -> Result<ReloadStatus, ReloadError>
+    where
+        B: ProgramBuilder,
+        F: ShaderFileSystem,
+    {
+        let mut sources = Vec::with_capacity(self.stages.len());
+        for (kind, path) in self.stages.iter() {
+            match fs.read_to_string(path) {
+                Ok(source) => sources.push((*kind, path, source)),
+                Err(error) => {
+                    return Err(ReloadError::Read {
+                        path: path.clone(),
+                        error,
+                    })
+                }
+            }
+        }
+
+        let mut shader_names = Vec::with_capacity(sources.len());
+        for (kind, path, source) in sources {
+            let shader_name = builder.create_shader(kind);
+            builder.shader_source(shader_name, &source);
+            if let Err(diagnostics) = builder.compile_shader_checked(shader_name) {
+                builder.delete_shader(shader_name);
+                for shader_name in shader_names {
+                    builder.delete_shader(shader_name);
+                }
+                return Err(ReloadError::Compile {
+                    path: path.clone(),
+                    diagnostics,
+                });
+            }
+            shader_names.push(shader_name);
+        }
+
+        let program_name = builder.create_program();
+        for &shader_name in shader_names.iter() {
+            builder.attach_shader(program_name, shader_name);
+        }
+        let linked = builder.link_program_checked(program_name);
+        // Attached shaders are deleted together with the program.
+        for shader_name in shader_names {
+            builder.delete_shader(shader_name);
+        }
+        if let Err(diagnostics) = linked {
+            builder.delete_program(program_name);
+            return Err(ReloadError::Link { diagnostics });
+        }
+
+        if let Some(old_program_name) = self.program_name.replace(program_name) {
+            builder.delete_program(old_program_name);
+        }
+        Ok(ReloadStatus::Reloaded)
+    }
+
+    pub unsafe fn delete<B: ProgramBuilder>(self, builder: &B) {
+        if let Some(program_name) = self.program_name {
+            builder.delete_program(program_name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::{Cell, RefCell};
+    use std::collections::{HashMap, HashSet};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    /// Fails to compile sources containing "error" and to link when any
+    /// source contains "unresolved".
+    #[derive(Default)]
+    struct MockGl {
+        next_name: Cell<u32>,
+        sources: RefCell<HashMap<ShaderName, String>>,
+        attached: RefCell<HashMap<ProgramName, Vec<ShaderName>>>,
+        live_shaders: RefCell<HashSet<ShaderName>>,
+        live_programs: RefCell<HashSet<ProgramName>>,
+    }
+
+    impl MockGl {
+        fn next_name(&self) -> u32 {
+            self.next_name.set(self.next_name.get() + 1);
+            self.next_name.get()
+        }
+
+        fn error(message: &str) -> Vec<ShaderDiagnostic> {
+            vec![ShaderDiagnostic {
+                severity: DiagnosticSeverity::Error,
+                source_index: None,
+                line: None,
+                column: None,
+                message: message.to_string(),
+            }]
+        }
+    }
+
+    impl ProgramBuilder for MockGl {
+        unsafe fn create_shader(&self, _kind: ShaderKind) -> ShaderName {
+            let shader_name = ShaderName::new(self.next_name()).unwrap();
+            self.live_shaders.borrow_mut().insert(shader_name);
+            shader_name
+        }
+
+        unsafe fn shader_source(&self, shader_name: ShaderName, source: &str) {
+            self.sources
+                .borrow_mut()
+                .insert(shader_name, source.to_string());
+        }
+
+        unsafe fn compile_shader_checked(
+            &self,
+            shader_name: ShaderName,
+        ) -> Result<(), Vec<ShaderDiagnostic>> {
+            if self.sources.borrow()[&shader_name].contains("error") {
+                Err(MockGl::error("syntax error"))
+            } else {
+                Ok(())
+            }
+        }
+
+        unsafe fn delete_shader(&self, shader_name: ShaderName) {
+            assert!(self.live_shaders.borrow_mut().remove(&shader_name));
+        }
+
+        unsafe fn create_program(&self) -> ProgramName {
+            let program_name = ProgramName::new(self.next_name()).unwrap();
+            self.live_programs.borrow_mut().insert(program_name);
+            program_name
+        }
+
+        unsafe fn attach_shader(&self, program_name: ProgramName, shader_name: ShaderName) {
+            self.attached
+                .borrow_mut()
+                .entry(program_name)
+                .or_default()
+                .push(shader_name);
+        }
+
+        unsafe fn link_program_checked(
+            &self,
+            program_name: ProgramName,
+        ) -> Result<(), Vec<ShaderDiagnostic>> {
+            let sources = self.sources.borrow();
+            if self.attached.borrow()[&program_name]
+                .iter()
+                .any(|shader_name| sources[shader_name].contains("unresolved"))
+            {
+                Err(MockGl::error("unresolved symbol"))
+            } else {
+                Ok(())
+            }
+        }
+
+        unsafe fn delete_program(&self, program_name: ProgramName) {
+            assert!(self.live_programs.borrow_mut().remove(&program_name));
+        }
+    }
+
+    #[derive(Default)]
+    struct MockFileSystem {
+        files: RefCell<HashMap<PathBuf, (u64, String)>>,
+    }
+
+    impl MockFileSystem {
+        fn write(&self, path: &str, modified: u64, source: &str) {
+            self.files
+                .borrow_mut()
+                .insert(PathBuf::from(path), (modified, source.to_string()));
+        }
+
+        fn get(&self, path: &Path) -> io::Result<(u64, String)> {
+            self.files
+                .borrow()
+                .get(path)
+                .cloned()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        }
+    }
+
+    impl ShaderFileSystem for MockFileSystem {
+        fn modified(&self, path: &Path) -> io::Result<SystemTime> {
+            self.get(path)
+                .map(|(modified, _)| UNIX_EPOCH + Duration::from_secs(modified))
+        }
+
+        fn read_to_string(&self, path: &Path) -> io::Result<String> {
+            self.get(path).map(|(_, source)| source)
+        }
+    }
+
+    fn program() -> ReloadableProgram {
+        ReloadableProgram::new(vec![
+            (ShaderKind::VertexShader, "a.vert"),
+            (ShaderKind::FragmentShader, "a.frag"),
+        ])
+        .track("common.glsl")
+    }
+
+    #[test]
+    fn builds_once_and_rebuilds_on_change() {
+        let gl = MockGl::default();
+        let fs = MockFileSystem::default();
+        fs.write("a.vert", 1, "vertex");
+        fs.write("a.frag", 1, "fragment");
+        fs.write("common.glsl", 1, "common");
+        let mut program = program();
+
+        unsafe {
+            assert_eq!(ReloadStatus::Reloaded, program.poll(&gl, &fs).unwrap());
+            let first = program.program_name().unwrap();
+            assert_eq!(ReloadStatus::Unchanged, program.poll(&gl, &fs).unwrap());
+            assert_eq!(Some(first), program.program_name());
+
+            fs.write("common.glsl", 2, "common");
+            assert_eq!(ReloadStatus::Reloaded, program.poll(&gl, &fs).unwrap());
+            let second = program.program_name().unwrap();
+            assert_ne!(first, second);
+
+            assert_eq!(1, gl.live_programs.borrow().len());
+            assert!(gl.live_shaders.borrow().is_empty());
+            program.delete(&gl);
+            assert!(gl.live_programs.borrow().is_empty());
+        }
+    }
+
+    #[test]
+    fn keeps_the_old_program_when_a_rebuild_fails() {
+        let gl = MockGl::default();
+        let fs = MockFileSystem::default();
+        fs.write("a.vert", 1, "vertex");
+        fs.write("a.frag", 1, "fragment");
+        let mut program = program();
+
+        unsafe {
+            program.poll(&gl, &fs).unwrap();
+            let first = program.program_name().unwrap();
+
+            fs.write("a.frag", 2, "fragment error");
+            match program.poll(&gl, &fs) {
+                Err(ReloadError::Compile { path, diagnostics }) => {
+                    assert_eq!(PathBuf::from("a.frag"), path);
+                    assert_eq!("syntax error", diagnostics[0].message);
+                }
+                other => panic!("{:?}", other),
+            }
+            assert_eq!(Some(first), program.program_name());
+            // Not retried until the file changes again.
+            assert_eq!(ReloadStatus::Unchanged, program.poll(&gl, &fs).unwrap());
+
+            fs.write("a.frag", 3, "fragment unresolved");
+            match program.poll(&gl, &fs) {
+                Err(ReloadError::Link { diagnostics }) => {
+                    assert_eq!("unresolved symbol", diagnostics[0].message)
+                }
+                other => panic!("{:?}", other),
+            }
+            assert_eq!(Some(first), program.program_name());
+
+            fs.files.borrow_mut().remove(Path::new("a.vert"));
+            match program.poll(&gl, &fs) {
+                Err(ReloadError::Read { path, .. }) => assert_eq!(PathBuf::from("a.vert"), path),
+                other => panic!("{:?}", other),
+            }
+            assert_eq!(Some(first), program.program_name());
+
+            fs.write("a.vert", 4, "vertex");
+            fs.write("a.frag", 4, "fragment");
+            assert_eq!(ReloadStatus::Reloaded, program.poll(&gl, &fs).unwrap());
+            assert_ne!(Some(first), program.program_name());
+
+            assert_eq!(1, gl.live_programs.borrow().len());
+            assert!(gl.live_shaders.borrow().is_empty());
+        }
+    }
+}
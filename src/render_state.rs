@@ -0,0 +1,597 @@
+//! A value type describing the fixed-function pipeline state and the logic to
+//! transition between two of them with as few OpenGL calls as possible.
+
+use crate::*;
+
+const CAPABILITIES: [Capability; 27] = [
+    Capability::Blend,
+    Capability::ColorLogicOp,
+    Capability::CullFace,
+    Capability::DebugOutput,
+    Capability::DebugOutputSynchronous,
+    Capability::DepthClamp,
+    Capability::DepthTest,
+    Capability::Dither,
+    Capability::FramebufferSrgb,
+    Capability::LineSmooth,
+    Capability::Multisample,
+    Capability::PolygonOffsetFill,
+    Capability::PolygonOffsetLine,
+    Capability::PolygonOffsetPoint,
+    Capability::PolygonSmooth,
+    Capability::PrimitiveRestart,
+    Capability::PrimitiveRestartFixedIndex,
+    Capability::RasterizerDiscard,
+    Capability::SampleAlphaToCoverage,
+    Capability::SampleAlphaToOne,
+    Capability::SampleCoverage,
+    Capability::SampleShading,
+    Capability::SampleMask,
+    Capability::ScissorTest,
+    Capability::StencilTest,
+    Capability::TextureCubeMapSeamless,
+    Capability::ProgramPointSize,
+];
+
+#[inline]
+fn capability_bit(cap: Capability) -> u32 {
+    let index = CAPABILITIES.iter().position(|&c| c == cap).unwrap();
+    1 << index
+}
+
+/// A set of enabled capabilities.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub struct CapabilitySet(u32);
+
+impl CapabilitySet {
+    #[inline]
+    pub fn empty() -> Self {
+        CapabilitySet(0)
+    }
+
+    #[inline]
+    pub fn contains(&self, cap: impl Into<Capability>) -> bool {
+        self.0 & capability_bit(cap.into()) != 0
+    }
+
+    #[inline]
+    pub fn insert(&mut self, cap: impl Into<Capability>) {
+        self.0 |= capability_bit(cap.into());
+    }
+
+    #[inline]
+    pub fn remove(&mut self, cap: impl Into<Capability>) {
+        self.0 &= !capability_bit(cap.into());
+    }
+
+    #[inline]
+    pub fn set(&mut self, cap: impl Into<Capability>, enabled: bool) {
+        if enabled {
+            self.insert(cap);
+        } else {
+            self.remove(cap);
+        }
+    }
+
+    #[inline]
+    pub fn with(mut self, cap: impl Into<Capability>) -> Self {
+        self.insert(cap);
+        self
+    }
+
+    #[inline]
+    pub fn without(mut self, cap: impl Into<Capability>) -> Self {
+        self.remove(cap);
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Capability> + '_ {
+        CAPABILITIES
+            .iter()
+            .cloned()
+            .filter(move |&cap| self.contains(cap))
+    }
+}
+
+impl std::iter::FromIterator<Capability> for CapabilitySet {
+    fn from_iter<I: IntoIterator<Item = Capability>>(iter: I) -> Self {
+        let mut set = CapabilitySet::empty();
+        for cap in iter {
+            set.insert(cap);
+        }
+        set
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct StencilFaceState {
+    pub func: StencilFunc,
+    pub reference: i32,
+    pub value_mask: u32,
+    pub write_mask: u32,
+    pub stencil_fail: StencilOp,
+    pub depth_fail: StencilOp,
+    pub depth_pass: StencilOp,
+}
+
+impl Default for StencilFaceState {
+    fn default() -> Self {
+        StencilFaceState {
+            func: StencilFunc::Always,
+            reference: 0,
+            value_mask: !0,
+            write_mask: !0,
+            stencil_fail: StencilOp::Keep,
+            depth_fail: StencilOp::Keep,
+            depth_pass: StencilOp::Keep,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlendState {
+    pub src_rgb: BlendFactor,
+    pub dst_rgb: BlendFactor,
+    pub src_alpha: BlendFactor,
+    pub dst_alpha: BlendFactor,
+    pub equation_rgb: BlendEquation,
+    pub equation_alpha: BlendEquation,
+    pub color: [f32; 4],
+}
+
+impl Default for BlendState {
+    fn default() -> Self {
+        BlendState {
+            src_rgb: BlendFactor::One,
+            dst_rgb: BlendFactor::Zero,
+            src_alpha: BlendFactor::One,
+            dst_alpha: BlendFactor::Zero,
+            equation_rgb: BlendEquation::FuncAdd,
+            equation_alpha: BlendEquation::FuncAdd,
+            color: [0.0; 4],
+        }
+    }
+}
+
+/// The state controlled by `glEnable`/`glDisable` and the fixed-function
+/// setters. The default value equals the initial OpenGL state, except for the
+/// viewport and the scissor box which OpenGL initializes to the size of the
+/// window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RenderState {
+    pub capabilities: CapabilitySet,
+    pub depth_func: DepthFunc,
+    pub depth_mask: WriteMask,
+    pub depth_range: [f64; 2],
+    pub stencil_front: StencilFaceState,
+    pub stencil_back: StencilFaceState,
+    pub blend: BlendState,
+    pub color_mask: [WriteMask; 4],
+    pub cull_face: CullFace,
+    pub front_face: FrontFace,
+    pub polygon_mode: PolygonMode,
+    pub polygon_offset: [f32; 2],
+    pub line_width: f32,
+    pub point_size: f32,
+    pub viewport: [i32; 4],
+    pub scissor: [i32; 4],
+    pub sample_coverage: (f32, bool),
+    pub sample_mask: u32,
+    pub logic_op: LogicOp,
+    pub primitive_restart_index: u32,
+    pub clip_control: (ClipControlOrigin, ClipControlDepth),
+}
+
+impl Default for RenderState {
+    fn default() -> Self {
+        RenderState {
+            capabilities: CapabilitySet::empty()
+                .with(Capability::Dither)
+                .with(Capability::Multisample),
+            depth_func: DepthFunc::LT,
+            depth_mask: WriteMask::Enabled,
+            depth_range: [0.0, 1.0],
+            stencil_front: StencilFaceState::default(),
+            stencil_back: StencilFaceState::default(),
+            blend: BlendState::default(),
+            color_mask: [WriteMask::Enabled; 4],
+            cull_face: CullFace::Back,
+            front_face: FrontFace::CCW,
+            polygon_mode: PolygonMode::Fill,
+            polygon_offset: [0.0, 0.0],
+            line_width: 1.0,
+            point_size: 1.0,
+            viewport: [0, 0, 0, 0],
+            scissor: [0, 0, 0, 0],
+            sample_coverage: (1.0, false),
+            sample_mask: !0,
+            logic_op: LogicOp::Copy,
+            primitive_restart_index: 0,
+            clip_control: (ClipControlOrigin::LowerLeft, ClipControlDepth::N1P1),
+        }
+    }
+}
+
+/// A single OpenGL call that changes the `RenderState`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StateCommand {
+    Enable(Capability),
+    Disable(Capability),
+    DepthFunc(DepthFunc),
+    DepthMask(WriteMask),
+    DepthRange(f64, f64),
+    StencilFuncSeparate(StencilFace, StencilFunc, i32, u32),
+    StencilOpSeparate(StencilFace, StencilOp, StencilOp, StencilOp),
+    StencilMaskSeparate(StencilFace, u32),
+    BlendFuncSeparate(BlendFactor, BlendFactor, BlendFactor, BlendFactor),
+    BlendEquationSeparate(BlendEquation, BlendEquation),
+    BlendColor([f32; 4]),
+    ColorMask([WriteMask; 4]),
+    CullFace(CullFace),
+    FrontFace(FrontFace),
+    PolygonMode(PolygonMode),
+    PolygonOffset(f32, f32),
+    LineWidth(f32),
+    PointSize(f32),
+    Viewport([i32; 4]),
+    Scissor([i32; 4]),
+    SampleCoverage(f32, bool),
+    SampleMask(u32),
+    LogicOp(LogicOp),
+    PrimitiveRestartIndex(u32),
+    ClipControl(ClipControlOrigin, ClipControlDepth),
+}
+
+/// Emits a stencil command for both faces at once when they are changed to the
+/// same value.
+fn diff_stencil<T, F>(
+    prev: [T; 2],
+    next: [T; 2],
+    f: &mut F,
+    command: impl Fn(StencilFace, T) -> StateCommand,
+) where
+    T: Copy + PartialEq,
+    F: FnMut(StateCommand),
+{
+    let [prev_front, prev_back] = prev;
+    let [next_front, next_back] = next;
+    let front_changed = prev_front != next_front;
+    let back_changed = prev_back != next_back;
+    if front_changed && back_changed && next_front == next_back {
+        f(command(StencilFace::FrontAndBack, next_front));
+    } else {
+        if front_changed {
+            f(command(StencilFace::Front, next_front));
+        }
+        if back_changed {
+            f(command(StencilFace::Back, next_back));
+        }
+    }
+}
+
+impl RenderState {
+    /// Calls `f` with the commands required to go from `self` to `next`.
+    pub fn diff<F>(&self, next: &RenderState, mut f: F)
+    where
+        F: FnMut(StateCommand),
+    {
+        let prev = self;
+
+        if prev.capabilities != next.capabilities {
+            for &cap in CAPABILITIES.iter() {
+                match (
+                    prev.capabilities.contains(cap),
+                    next.capabilities.contains(cap),
+                ) {
+                    (false, true) => f(StateCommand::Enable(cap)),
+                    (true, false) => f(StateCommand::Disable(cap)),
+                    _ => {}
+                }
+            }
+        }
+
+        if prev.depth_func != next.depth_func {
+            f(StateCommand::DepthFunc(next.depth_func));
+        }
+
+        if prev.depth_mask != next.depth_mask {
+            f(StateCommand::DepthMask(next.depth_mask));
+        }
+
+        if prev.depth_range != next.depth_range {
+            let [near, far] = next.depth_range;
+            f(StateCommand::DepthRange(near, far));
+        }
+
+        let stencil_func = |s: &StencilFaceState| (s.func, s.reference, s.value_mask);
+        let stencil_op = |s: &StencilFaceState| (s.stencil_fail, s.depth_fail, s.depth_pass);
+        let stencil_mask = |s: &StencilFaceState| s.write_mask;
+
+        diff_stencil(
+            [
+                stencil_func(&prev.stencil_front),
+                stencil_func(&prev.stencil_back),
+            ],
+            [
+                stencil_func(&next.stencil_front),
+                stencil_func(&next.stencil_back),
+            ],
+            &mut f,
+            |face, (func, reference, mask)| {
+                StateCommand::StencilFuncSeparate(face, func, reference, mask)
+            },
+        );
+
+        diff_stencil(
+            [
+                stencil_op(&prev.stencil_front),
+                stencil_op(&prev.stencil_back),
+            ],
+            [
+                stencil_op(&next.stencil_front),
+                stencil_op(&next.stencil_back),
+            ],
+            &mut f,
+            |face, (stencil_fail, depth_fail, depth_pass)| {
+                StateCommand::StencilOpSeparate(face, stencil_fail, depth_fail, depth_pass)
+            },
+        );
+
+        diff_stencil(
+            [
+                stencil_mask(&prev.stencil_front),
+                stencil_mask(&prev.stencil_back),
+            ],
+            [
+                stencil_mask(&next.stencil_front),
+                stencil_mask(&next.stencil_back),
+            ],
+            &mut f,
+            StateCommand::StencilMaskSeparate,
+        );
+
+        let (pb, nb) = (&prev.blend, &next.blend);
+
+        if (pb.src_rgb, pb.dst_rgb, pb.src_alpha, pb.dst_alpha)
+            != (nb.src_rgb, nb.dst_rgb, nb.src_alpha, nb.dst_alpha)
+        {
+            f(StateCommand::BlendFuncSeparate(
+                nb.src_rgb,
+                nb.dst_rgb,
+                nb.src_alpha,
+                nb.dst_alpha,
+            ));
+        }
+
+        if (pb.equation_rgb, pb.equation_alpha) != (nb.equation_rgb, nb.equation_alpha) {
+            f(StateCommand::BlendEquationSeparate(
+                nb.equation_rgb,
+                nb.equation_alpha,
+            ));
+        }
+
+        if pb.color != nb.color {
+            f(StateCommand::BlendColor(nb.color));
+        }
+
+        if prev.color_mask != next.color_mask {
+            f(StateCommand::ColorMask(next.color_mask));
+        }
+
+        if prev.cull_face != next.cull_face {
+            f(StateCommand::CullFace(next.cull_face));
+        }
+
+        if prev.front_face != next.front_face {
+            f(StateCommand::FrontFace(next.front_face));
+        }
+
+        if prev.polygon_mode != next.polygon_mode {
+            f(StateCommand::PolygonMode(next.polygon_mode));
+        }
+
+        if prev.polygon_offset != next.polygon_offset {
+            let [factor, units] = next.polygon_offset;
+            f(StateCommand::PolygonOffset(factor, units));
+        }
+
+        if prev.line_width != next.line_width {
+            f(StateCommand::LineWidth(next.line_width));
+        }
+
+        if prev.point_size != next.point_size {
+            f(StateCommand::PointSize(next.point_size));
+        }
+
+        if prev.viewport != next.viewport {
+            f(StateCommand::Viewport(next.viewport));
+        }
+
+        if prev.scissor != next.scissor {
+            f(StateCommand::Scissor(next.scissor));
+        }
+
+        if prev.sample_coverage != next.sample_coverage {
+            let (value, invert) = next.sample_coverage;
+            f(StateCommand::SampleCoverage(value, invert));
+        }
+
+        if prev.sample_mask != next.sample_mask {
+            f(StateCommand::SampleMask(next.sample_mask));
+        }
+
+        if prev.logic_op != next.logic_op {
+            f(StateCommand::LogicOp(next.logic_op));
+        }
+
+        if prev.primitive_restart_index != next.primitive_restart_index {
+            f(StateCommand::PrimitiveRestartIndex(
+                next.primitive_restart_index,
+            ));
+        }
+
+        if prev.clip_control != next.clip_control {
+            let (origin, depth) = next.clip_control;
+            f(StateCommand::ClipControl(origin, depth));
+        }
+    }
+}
+
+impl Gl {
+    /// Issues only the calls needed to go from `prev` to `next`. The caller is
+    /// responsible for `prev` matching the actual OpenGL state.
+    #[inline]
+    pub unsafe fn apply_state(&self, prev: &RenderState, next: &RenderState) {
+        prev.diff(next, |command| self.apply_state_command(command));
+    }
+
+    #[inline]
+    pub unsafe fn apply_state_command(&self, command: StateCommand) {
+        match command {
+            StateCommand::Enable(cap) => self.enable(cap),
+            StateCommand::Disable(cap) => self.disable(cap),
+            StateCommand::DepthFunc(func) => self.depth_func(func),
+            StateCommand::DepthMask(mask) => self.depth_mask(mask),
+            StateCommand::DepthRange(n, f) => self.depth_range(n, f),
+            StateCommand::StencilFuncSeparate(face, func, reference, mask) => {
+                self.stencil_func_separate(face, func, reference, mask)
+            }
+            StateCommand::StencilOpSeparate(face, sfail, dpfail, dppass) => {
+                self.stencil_op_separate(face, sfail, dpfail, dppass)
+            }
+            StateCommand::StencilMaskSeparate(face, mask) => self.stencil_mask_separate(face, mask),
+            StateCommand::BlendFuncSeparate(src_rgb, dst_rgb, src_alpha, dst_alpha) => {
+                self.blend_func_separate(src_rgb, dst_rgb, src_alpha, dst_alpha)
+            }
+            StateCommand::BlendEquationSeparate(rgb, alpha) => {
+                self.blend_equation_separate(rgb, alpha)
+            }
+            StateCommand::BlendColor([r, g, b, a]) => self.blend_color(r, g, b, a),
+            StateCommand::ColorMask([r, g, b, a]) => self.color_mask(r, g, b, a),
+            StateCommand::CullFace(face) => self.cull_face(face),
+            StateCommand::FrontFace(face) => self.front_face(face),
+            StateCommand::PolygonMode(mode) => {
+                self.polygon_mode(PolygonModeFace::FrontAndBack, mode)
+            }
+            StateCommand::PolygonOffset(factor, units) => self.polygon_offset(factor, units),
+            StateCommand::LineWidth(width) => self.line_width(width),
+            StateCommand::PointSize(size) => self.point_size(size),
+            StateCommand::Viewport([x, y, width, height]) => self.viewport(x, y, width, height),
+            StateCommand::Scissor([x, y, width, height]) => self.scissor(x, y, width, height),
+            StateCommand::SampleCoverage(value, invert) => self.sample_coverage(value, invert),
+            StateCommand::SampleMask(mask) => self.sample_maski(0, mask),
+            StateCommand::LogicOp(op) => self.logic_op(op),
+            StateCommand::PrimitiveRestartIndex(index) => self.primitive_restart_index(index),
+            StateCommand::ClipControl(origin, depth) => self.clip_control(origin, depth),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(prev: &RenderState, next: &RenderState) -> Vec<StateCommand> {
+        let mut commands = Vec::new();
+        prev.diff(next, |command| commands.push(command));
+        commands
+    }
+
+    #[test]
+    fn identical_states_issue_nothing() {
+        let state = RenderState::default();
+        assert_eq!(diff(&state, &state), vec![]);
+    }
+
+    #[test]
+    fn capabilities_are_toggled_individually() {
+        let prev = RenderState::default();
+        let next = RenderState {
+            capabilities: prev
+                .capabilities
+                .with(DEPTH_TEST)
+                .with(CULL_FACE)
+                .without(DITHER),
+            ..prev
+        };
+        assert_eq!(
+            diff(&prev, &next),
+            vec![
+                StateCommand::Enable(Capability::CullFace),
+                StateCommand::Enable(Capability::DepthTest),
+                StateCommand::Disable(Capability::Dither),
+            ]
+        );
+        assert_eq!(
+            next.capabilities.iter().collect::<Vec<_>>(),
+            vec![
+                Capability::CullFace,
+                Capability::DepthTest,
+                Capability::Multisample,
+            ]
+        );
+    }
+
+    #[test]
+    fn only_changed_fields_are_issued() {
+        let prev = RenderState::default();
+        let next = RenderState {
+            depth_func: DepthFunc::GE,
+            blend: BlendState {
+                src_rgb: BlendFactor::SrcAlpha,
+                dst_rgb: BlendFactor::OneMinusSrcAlpha,
+                ..prev.blend
+            },
+            viewport: [0, 0, 1280, 720],
+            ..prev
+        };
+        assert_eq!(
+            diff(&prev, &next),
+            vec![
+                StateCommand::DepthFunc(DepthFunc::GE),
+                StateCommand::BlendFuncSeparate(
+                    BlendFactor::SrcAlpha,
+                    BlendFactor::OneMinusSrcAlpha,
+                    BlendFactor::One,
+                    BlendFactor::Zero,
+                ),
+                StateCommand::Viewport([0, 0, 1280, 720]),
+            ]
+        );
+    }
+
+    #[test]
+    fn stencil_faces_are_merged_when_equal() {
+        let prev = RenderState::default();
+        let face = StencilFaceState {
+            depth_pass: StencilOp::Replace,
+            ..prev.stencil_front
+        };
+        let both = RenderState {
+            stencil_front: face,
+            stencil_back: face,
+            ..prev
+        };
+        assert_eq!(
+            diff(&prev, &both),
+            vec![StateCommand::StencilOpSeparate(
+                StencilFace::FrontAndBack,
+                StencilOp::Keep,
+                StencilOp::Keep,
+                StencilOp::Replace,
+            )]
+        );
+
+        let back_only = RenderState {
+            stencil_back: StencilFaceState {
+                write_mask: 0xFF,
+                ..prev.stencil_back
+            },
+            ..prev
+        };
+        assert_eq!(
+            diff(&prev, &back_only),
+            vec![StateCommand::StencilMaskSeparate(StencilFace::Back, 0xFF)]
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use crate::*;
+use std::fmt;
+
+/// An implementation defined shader binary format. The supported formats can
+/// be queried with `get_shader_binary_formats`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[repr(transparent)]
+pub struct ShaderBinaryFormat(pub u32);
+
+impl ShaderBinaryFormat {
+    #[cfg(feature = "spirv")]
+    pub const SPIR_V: Self = ShaderBinaryFormat(gl::SHADER_BINARY_FORMAT_SPIR_V_ARB);
+}
+
+pub const SPIRV_MAGIC_NUMBER: u32 = 0x0723_0203;
+
+/// The fields of a SPIR-V module header.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SpirvHeader {
+    pub major_version: u8,
+    pub minor_version: u8,
+    pub generator: u32,
+    /// All ids in the module are less than this.
+    pub bound: u32,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SpirvHeaderError {
+    /// The module is not a whole number of words long.
+    Length(usize),
+    /// The module is shorter than its 5 word header.
+    TooShort(usize),
+    /// The module does not start with `SPIRV_MAGIC_NUMBER`. A byte swapped
+    /// magic number means the module was written with the wrong endianness.
+    MagicNumber(u32),
+    /// Only versions 1.0 through 1.6 exist.
+    Version(u32),
+    /// The reserved schema word is not zero.
+    Schema(u32),
+}
+
+impl fmt::Display for SpirvHeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpirvHeaderError::Length(length) => write!(
+                f,
+                "SPIR-V module length {} is not a multiple of 4 bytes.",
+                length
+            ),
+            SpirvHeaderError::TooShort(length) => write!(
+                f,
+                "SPIR-V module of {} bytes is too short to contain a header.",
+                length
+            ),
+            SpirvHeaderError::MagicNumber(magic) if magic.swap_bytes() == SPIRV_MAGIC_NUMBER => {
+                write!(f, "SPIR-V module has the wrong endianness.")
+            }
+            SpirvHeaderError::MagicNumber(magic) => {
+                write!(f, "Invalid SPIR-V magic number {:#010x}.", magic)
+            }
+            SpirvHeaderError::Version(version) => {
+                write!(f, "Unsupported SPIR-V version word {:#010x}.", version)
+            }
+            SpirvHeaderError::Schema(schema) => {
+                write!(f, "Invalid SPIR-V schema {}.", schema)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SpirvHeaderError {}
+
+/// Checks the header of a SPIR-V module in native endianness.
+// `is_multiple_of` needs a newer compiler than this crate supports.
+#[allow(clippy::manual_is_multiple_of)]
+pub fn validate_spirv_header(module: &[u8]) -> Result<SpirvHeader, SpirvHeaderError> {
+    if module.len() % 4 != 0 {
+        return Err(SpirvHeaderError::Length(module.len()));
+    }
+    if module.len() < 20 {
+        return Err(SpirvHeaderError::TooShort(module.len()));
+    }
+    let word = |index: usize| {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(&module[index * 4..index * 4 + 4]);
+        u32::from_ne_bytes(bytes)
+    };
+
+    let magic = word(0);
+    if magic != SPIRV_MAGIC_NUMBER {
+        return Err(SpirvHeaderError::MagicNumber(magic));
+    }
+
+    // 0x00MMmm00
+    let version = word(1);
+    let major_version = (version >> 16) as u8;
+    let minor_version = (version >> 8) as u8;
+    if version & 0xFF00_00FF != 0 || major_version != 1 || minor_version > 6 {
+        return Err(SpirvHeaderError::Version(version));
+    }
+
+    let schema = word(4);
+    if schema != 0 {
+        return Err(SpirvHeaderError::Schema(schema));
+    }
+
+    Ok(SpirvHeader {
+        major_version,
+        minor_version,
+        generator: word(2),
+        bound: word(3),
+    })
+}
+
+impl Gl {
+    #[inline]
+    pub unsafe fn get_shader_binary_formats(&self) -> Vec<ShaderBinaryFormat> {
+        let mut count = MaybeUninit::<i32>::uninit();
+        self.gl
+            .GetIntegerv(gl::NUM_SHADER_BINARY_FORMATS, count.as_mut_ptr());
+        let mut formats = vec![ShaderBinaryFormat(0); count.assume_init() as usize];
+        if !formats.is_empty() {
+            self.gl
+                .GetIntegerv(gl::SHADER_BINARY_FORMATS, formats.as_mut_ptr() as *mut i32);
+        }
+        formats
+    }
+
+    /// Loads the same binary into every shader in `shader_names`.
+    #[inline]
+    pub unsafe fn shader_binary(
+        &self,
+        shader_names: &[ShaderName],
+        format: ShaderBinaryFormat,
+        binary: &[u8],
+    ) {
+        self.gl.ShaderBinary(
+            shader_names.len() as i32,
+            shader_names.as_ptr() as *const u32,
+            format.0,
+            binary.as_ptr() as *const c_void,
+            binary.len() as i32,
+        );
+    }
+
+    /// Validates the header of `module` and loads it into `shader_name`. The
+    /// shader still has to be specialized with `specialize_shader`.
+    #[cfg(feature = "spirv")]
+    #[inline]
+    pub unsafe fn shader_binary_spirv(
+        &self,
+        shader_name: ShaderName,
+        module: &[u8],
+    ) -> Result<SpirvHeader, SpirvHeaderError> {
+        let header = validate_spirv_header(module)?;
+        self.shader_binary(&[shader_name], ShaderBinaryFormat::SPIR_V, module);
+        Ok(header)
+    }
+
+    /// Selects the entry point of a SPIR-V shader and sets its specialization
+    /// constants as `(index, value)` pairs. The value is the bit pattern of
+    /// the constant. Check `COMPILE_STATUS` afterwards.
+    #[cfg(feature = "spirv")]
+    #[inline]
+    pub unsafe fn specialize_shader(
+        &self,
+        shader_name: ShaderName,
+        entry_point: &CStr,
+        constants: &[(u32, u32)],
+    ) {
+        let (indices, values): (Vec<u32>, Vec<u32>) = constants.iter().cloned().unzip();
+        self.gl.SpecializeShaderARB(
+            shader_name.to_u32(),
+            entry_point.as_ptr(),
+            constants.len() as u32,
+            indices.as_ptr(),
+            values.as_ptr(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_ne_bytes()).collect()
+    }
+
+    #[test]
+    fn accepts_valid_headers() {
+        assert_eq!(
+            Ok(SpirvHeader {
+                major_version: 1,
+                minor_version: 3,
+                generator: 0x0008_000a,
+                bound: 42,
+            }),
+            validate_spirv_header(&module(&[
+                SPIRV_MAGIC_NUMBER,
+                0x0001_0300,
+                0x0008_000a,
+                42,
+                0,
+                0x0002_0011,
+            ]))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_headers() {
+        assert_eq!(
+            Err(SpirvHeaderError::Length(21)),
+            validate_spirv_header(&[0; 21])
+        );
+        assert_eq!(
+            Err(SpirvHeaderError::TooShort(16)),
+            validate_spirv_header(&module(&[SPIRV_MAGIC_NUMBER, 0x0001_0000, 0, 1]))
+        );
+        let swapped = SPIRV_MAGIC_NUMBER.swap_bytes();
+        assert_eq!(
+            Err(SpirvHeaderError::MagicNumber(swapped)),
+            validate_spirv_header(&module(&[swapped, 0x0001_0000, 0, 1, 0]))
+        );
+        assert_eq!(
+            "SPIR-V module has the wrong endianness.",
+            SpirvHeaderError::MagicNumber(swapped).to_string()
+        );
+        for &version in [0x0002_0000, 0x0001_0700, 0x0001_0001].iter() {
+            assert_eq!(
+                Err(SpirvHeaderError::Version(version)),
+                validate_spirv_header(&module(&[SPIRV_MAGIC_NUMBER, version, 0, 1, 0]))
+            );
+        }
+        assert_eq!(
+            Err(SpirvHeaderError::Schema(3)),
+            validate_spirv_header(&module(&[SPIRV_MAGIC_NUMBER, 0x0001_0000, 0, 1, 3]))
+        );
+    }
+}
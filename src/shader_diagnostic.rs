@@ -0,0 +1,337 @@
+use crate::*;
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum DiagnosticSeverity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for DiagnosticSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            DiagnosticSeverity::Warning => "warning",
+            DiagnosticSeverity::Error => "error",
+        })
+    }
+}
+
+/// A single message from a shader or program info log. Link errors usually
+/// have no location.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ShaderDiagnostic {
+    pub severity: DiagnosticSeverity,
+    /// The source string number as set by `shader_source` or `#line`.
+    pub source_index: Option<u32>,
+    pub line: Option<u32>,
+    /// Only reported by Mesa.
+    pub column: Option<u32>,
+    pub message: String,
+}
+
+impl fmt::Display for ShaderDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(source_index) = self.source_index {
+            write!(f, "{}:", source_index)?;
+            if let Some(line) = self.line {
+                write!(f, "{}:", line)?;
+                if let Some(column) = self.column {
+                    write!(f, "{}:", column)?;
+                }
+            }
+            f.write_str(" ")?;
+        }
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+impl std::error::Error for ShaderDiagnostic {}
+
+fn number_prefix(s: &str) -> Option<(u32, &str)> {
+    let end = s
+        .char_indices()
+        .find(|&(_, c)| !c.is_ascii_digit())
+        .map_or(s.len(), |(i, _)| i);
+    if end == 0 {
+        return None;
+    }
+    s[..end].parse().ok().map(|n| (n, &s[end..]))
+}
+
+fn severity_prefix(s: &str) -> Option<(DiagnosticSeverity, &str)> {
+    let lower = s.get(..7).map(|p| p.to_ascii_lowercase());
+    match lower.as_deref() {
+        Some("warning") => Some((DiagnosticSeverity::Warning, &s[7..])),
+        _ => match s.get(..5).map(|p| p.to_ascii_lowercase()).as_deref() {
+            Some("error") => Some((DiagnosticSeverity::Error, &s[5..])),
+            _ => None,
+        },
+    }
+}
+
+/// Parses `0:12` followed by a colon.
+fn source_line(s: &str) -> Option<(u32, u32, &str)> {
+    let (source_index, rest) = number_prefix(s)?;
+    let (line, rest) = number_prefix(rest.strip_prefix(':')?)?;
+    Some((source_index, line, rest.strip_prefix(':')?))
+}
+
+fn diagnostic(
+    severity: DiagnosticSeverity,
+    location: Option<(u32, u32)>,
+    column: Option<u32>,
+    message: &str,
+) -> ShaderDiagnostic {
+    ShaderDiagnostic {
+        severity,
+        source_index: location.map(|(source_index, _)| source_index),
+        line: location.map(|(_, line)| line),
+        column,
+        message: message.trim().to_string(),
+    }
+}
+
+/// Parses a single line of an info log.
+///
+/// - NVIDIA: `0(12) : error C0000: message`
+/// - Mesa and Intel on Linux: `0:12(3): error: message`
+/// - AMD and Intel on Windows: `ERROR: 0:12: message`
+/// - Link errors: `error: message`
+pub fn parse_shader_diagnostic(line: &str) -> Option<ShaderDiagnostic> {
+    let line = line.trim();
+
+    if let Some((severity, rest)) = severity_prefix(line) {
+        let rest = rest.strip_prefix(':')?.trim_start();
+        return match source_line(rest) {
+            Some((source_index, line, message)) => Some(diagnostic(
+                severity,
+                Some((source_index, line)),
+                None,
+                message,
+            )),
+            // Summaries like "ERROR: 2 compilation errors.  No code generated."
+            None if rest.starts_with(|c: char| c.is_ascii_digit()) => None,
+            None => Some(diagnostic(severity, None, None, rest)),
+        };
+    }
+
+    let (source_index, rest) = number_prefix(line)?;
+
+    if let Some(rest) = rest.strip_prefix('(') {
+        // NVIDIA.
+        let (line, rest) = number_prefix(rest)?;
+        let rest = rest.strip_prefix(')')?.trim_start().strip_prefix(':')?;
+        let (severity, message) = severity_prefix(rest.trim_start())?;
+        return Some(diagnostic(
+            severity,
+            Some((source_index, line)),
+            None,
+            message,
+        ));
+    }
+
+    // Mesa.
+    let (line, rest) = number_prefix(rest.strip_prefix(':')?)?;
+    let (column, rest) = match rest.strip_prefix('(') {
+        Some(rest) => {
+            let (column, rest) = number_prefix(rest)?;
+            (Some(column), rest.strip_prefix(')')?)
+        }
+        None => (None, rest),
+    };
+    let (severity, message) = severity_prefix(rest.strip_prefix(':')?.trim_start())?;
+    Some(diagnostic(
+        severity,
+        Some((source_index, line)),
+        column,
+        message.strip_prefix(':').unwrap_or(message),
+    ))
+}
+
+/// Parses every recognized line of an info log. Other lines are skipped.
+pub fn parse_shader_diagnostics(log: &str) -> Vec<ShaderDiagnostic> {
+    log.lines().filter_map(parse_shader_diagnostic).collect()
+}
+
+/// Reports a failure with the whole log when no line could be parsed.
+fn failure_diagnostics(log: &str) -> Vec<ShaderDiagnostic> {
+    let diagnostics: Vec<ShaderDiagnostic> = parse_shader_diagnostics(log)
+        .into_iter()
+        .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+        .collect();
+    if diagnostics.is_empty() {
+        vec![diagnostic(DiagnosticSeverity::Error, None, None, log)]
+    } else {
+        diagnostics
+    }
+}
+
+impl Gl {
+    /// Compiles a shader and returns the errors in its info log when the
+    /// compilation fails.
+    pub unsafe fn compile_shader_checked(
+        &self,
+        shader_name: ShaderName,
+    ) -> Result<(), Vec<ShaderDiagnostic>> {
+        self.compile_shader(shader_name);
+        match self.get_shaderiv(shader_name, COMPILE_STATUS) {
+            CompileStatus::Compiled => Ok(()),
+            CompileStatus::Uncompiled => {
+                Err(failure_diagnostics(&self.get_shader_info_log(shader_name)))
+            }
+        }
+    }
+
+    /// Links a program and returns the errors in its info log when linking
+    /// fails.
+    pub unsafe fn link_program_checked(
+        &self,
+        program_name: ProgramName,
+    ) -> Result<(), Vec<ShaderDiagnostic>> {
+        self.link_program(program_name);
+        match self.get_programiv(program_name, LINK_STATUS) {
+            LinkStatus::Linked => Ok(()),
+            LinkStatus::Unlinked => Err(failure_diagnostics(
+                &self.get_program_info_log(program_name),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DiagnosticSeverity::{Error, Warning};
+
+    const NVIDIA_LOG: &str = "\
+0(12) : error C1008: undefined variable \"vs_pos\"
+0(14) : warning C7050: \"color\" might be used before being initialized
+";
+
+    const MESA_LOG: &str = "\
+0:12(17): error: `vs_pos' undeclared
+0:12(17): error: operands to arithmetic operators must be numeric
+1:3(1): warning: extension `GL_ARB_foo' unsupported in vertex shader
+";
+
+    const AMD_LOG: &str = "\
+ERROR: 0:12: 'vs_pos' : undeclared identifier
+WARNING: 0:4: 'GL_ARB_foo' : extension is not supported
+ERROR: 1 compilation errors.  No code generated.
+";
+
+    const INTEL_LOG: &str = "\
+ERROR: 0:12: 'vs_pos' : undeclared identifier
+ERROR: 0:12: 'assign' :  cannot convert from 'float' to 'highp 4-component vector of float'
+";
+
+    const MESA_LINK_LOG: &str = "\
+error: fragment shader input `vs_color' has no matching output in the previous stage
+";
+
+    fn located(
+        severity: DiagnosticSeverity,
+        source_index: u32,
+        line: u32,
+        column: Option<u32>,
+        message: &str,
+    ) -> ShaderDiagnostic {
+        ShaderDiagnostic {
+            severity,
+            source_index: Some(source_index),
+            line: Some(line),
+            column,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_nvidia() {
+        assert_eq!(
+            vec![
+                located(Error, 0, 12, None, "C1008: undefined variable \"vs_pos\""),
+                located(
+                    Warning,
+                    0,
+                    14,
+                    None,
+                    "C7050: \"color\" might be used before being initialized"
+                ),
+            ],
+            parse_shader_diagnostics(NVIDIA_LOG)
+        );
+    }
+
+    #[test]
+    fn parses_mesa() {
+        assert_eq!(
+            vec![
+                located(Error, 0, 12, Some(17), "`vs_pos' undeclared"),
+                located(
+                    Error,
+                    0,
+                    12,
+                    Some(17),
+                    "operands to arithmetic operators must be numeric"
+                ),
+                located(
+                    Warning,
+                    1,
+                    3,
+                    Some(1),
+                    "extension `GL_ARB_foo' unsupported in vertex shader"
+                ),
+            ],
+            parse_shader_diagnostics(MESA_LOG)
+        );
+    }
+
+    #[test]
+    fn parses_amd_and_intel() {
+        assert_eq!(
+            vec![
+                located(Error, 0, 12, None, "'vs_pos' : undeclared identifier"),
+                located(
+                    Warning,
+                    0,
+                    4,
+                    None,
+                    "'GL_ARB_foo' : extension is not supported"
+                ),
+            ],
+            parse_shader_diagnostics(AMD_LOG)
+        );
+        assert_eq!(
+            vec![
+                located(Error, 0, 12, None, "'vs_pos' : undeclared identifier"),
+                located(
+                    Error,
+                    0,
+                    12,
+                    None,
+                    "'assign' :  cannot convert from 'float' to 'highp 4-component vector of float'"
+                ),
+            ],
+            parse_shader_diagnostics(INTEL_LOG)
+        );
+    }
+
+    #[test]
+    fn parses_link_errors_and_falls_back_to_the_log() {
+        let diagnostics = parse_shader_diagnostics(MESA_LINK_LOG);
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(None, diagnostics[0].line);
+        assert_eq!(
+            "error: fragment shader input `vs_color' has no matching output in the previous stage",
+            diagnostics[0].to_string()
+        );
+
+        let diagnostics = failure_diagnostics("Link failed.\n");
+        assert_eq!(1, diagnostics.len());
+        assert_eq!("Link failed.", diagnostics[0].message);
+        assert_eq!(
+            "0:12:17: error: `vs_pos' undeclared",
+            parse_shader_diagnostics(MESA_LOG)[0].to_string()
+        );
+    }
+}
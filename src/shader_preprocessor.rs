@@ -0,0 +1,453 @@
+use crate::*;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Provides the source of the files named in `#include` directives.
+pub trait IncludeResolver {
+    fn resolve(&mut self, path: &str) -> io::Result<String>;
+}
+
+impl IncludeResolver for HashMap<String, String> {
+    fn resolve(&mut self, path: &str) -> io::Result<String> {
+        self.get(path)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, path.to_string()))
+    }
+}
+
+/// Reads includes from the file system relative to `root`.
+#[derive(Debug, Clone)]
+pub struct FileResolver {
+    pub root: PathBuf,
+}
+
+impl FileResolver {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        FileResolver { root: root.into() }
+    }
+}
+
+impl IncludeResolver for FileResolver {
+    fn resolve(&mut self, path: &str) -> io::Result<String> {
+        fs::read_to_string(self.root.join(path))
+    }
+}
+
+#[derive(Debug)]
+pub enum PreprocessError {
+    /// The resolver could not provide a file.
+    Resolve {
+        path: String,
+        file: String,
+        line: u32,
+        error: io::Error,
+    },
+    /// A file includes itself, directly or through other files.
+    Recursive {
+        path: String,
+        file: String,
+        line: u32,
+    },
+    /// An `#include` not followed by a quoted path.
+    MalformedInclude { file: String, line: u32 },
+}
+
+impl fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PreprocessError::Resolve {
+                path,
+                file,
+                line,
+                error,
+            } => write!(
+                f,
+                "{}:{}: Failed to include \"{}\": {}",
+                file, line, path, error
+            ),
+            PreprocessError::Recursive { path, file, line } => {
+                write!(f, "{}:{}: Recursive include of \"{}\".", file, line, path)
+            }
+            PreprocessError::MalformedInclude { file, line } => {
+                write!(f, "{}:{}: Expected #include \"path\".", file, line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PreprocessError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PreprocessError::Resolve { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Combines a shader and its includes into a single source string.
+///
+/// Every file is assigned a source string number and `#line` directives are
+/// emitted around includes so that the line numbers in the info log refer to
+/// the original files. Files containing `#pragma once` are included once.
+#[derive(Debug, Clone, Default)]
+pub struct ShaderPreprocessor {
+    version: Option<String>,
+    defines: Vec<(String, String)>,
+}
+
+/// The output of `ShaderPreprocessor::process`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PreprocessedShader {
+    pub source: String,
+    /// The file names indexed by source string number.
+    pub files: Vec<String>,
+}
+
+/// A message from an info log along with the file and line it refers to.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SourceDiagnostic {
+    pub file: String,
+    pub line: u32,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+}
+
+impl ShaderPreprocessor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces any `#version` directive in the sources, for example with
+    /// `"450 core"`.
+    pub fn version<S: Into<String>>(mut self, version: S) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn define<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.defines.push((name.into(), value.into()));
+        self
+    }
+
+    /// Reads `path` through `resolver` and expands its includes.
+    pub fn process<R: IncludeResolver>(
+        &self,
+        resolver: &mut R,
+        path: &str,
+    ) -> Result<PreprocessedShader, PreprocessError> {
+        let mut state = State {
+            resolver,
+            files: Vec::new(),
+            once: Vec::new(),
+            stack: Vec::new(),
+            version: self.version.clone(),
+            body: String::new(),
+        };
+        let source = state
+            .resolver
+            .resolve(path)
+            .map_err(|error| PreprocessError::Resolve {
+                path: path.to_string(),
+                file: path.to_string(),
+                line: 0,
+                error,
+            })?;
+        state.expand(path, &source)?;
+
+        let mut output = String::new();
+        if let Some(version) = &state.version {
+            output.push_str("#version ");
+            output.push_str(version);
+            output.push('\n');
+        }
+        for (name, value) in self.defines.iter() {
+            output.push_str("#define ");
+            output.push_str(name);
+            if !value.is_empty() {
+                output.push(' ');
+                output.push_str(value);
+            }
+            output.push('\n');
+        }
+        output.push_str(&state.body);
+
+        Ok(PreprocessedShader {
+            source: output,
+            files: state.files,
+        })
+    }
+}
+
+struct State<'r, R> {
+    resolver: &'r mut R,
+    files: Vec<String>,
+    /// Source string numbers of the files containing `#pragma once`.
+    once: Vec<usize>,
+    stack: Vec<String>,
+    version: Option<String>,
+    body: String,
+}
+
+impl<'r, R: IncludeResolver> State<'r, R> {
+    fn file_index(&mut self, path: &str) -> usize {
+        match self.files.iter().position(|file| file == path) {
+            Some(index) => index,
+            None => {
+                self.files.push(path.to_string());
+                self.files.len() - 1
+            }
+        }
+    }
+
+    // `is_some_and` needs a newer compiler than this crate supports.
+    #[allow(clippy::unnecessary_map_or)]
+    fn expand(&mut self, path: &str, source: &str) -> Result<(), PreprocessError> {
+        let index = self.file_index(path);
+        self.stack.push(path.to_string());
+        self.body.push_str(&format!("#line 1 {}\n", index));
+
+        for (line_index, line) in source.lines().enumerate() {
+            let line_number = line_index as u32 + 1;
+            let directive = match directive(line) {
+                Some(directive) => directive,
+                None => {
+                    self.body.push_str(line);
+                    self.body.push('\n');
+                    continue;
+                }
+            };
+
+            if let Some(rest) = keyword(directive, "version") {
+                // The first version directive wins unless one was injected.
+                if self.version.is_none() {
+                    self.version = Some(rest.trim().to_string());
+                }
+                self.body.push('\n');
+            } else if keyword(directive, "pragma")
+                .map(|rest| rest.trim() == "once")
+                .unwrap_or(false)
+            {
+                if !self.once.contains(&index) {
+                    self.once.push(index);
+                }
+                self.body.push('\n');
+            } else if let Some(rest) = keyword(directive, "include") {
+                let include = quoted(rest).ok_or_else(|| PreprocessError::MalformedInclude {
+                    file: path.to_string(),
+                    line: line_number,
+                })?;
+                if self.stack.iter().any(|file| file == include) {
+                    return Err(PreprocessError::Recursive {
+                        path: include.to_string(),
+                        file: path.to_string(),
+                        line: line_number,
+                    });
+                }
+                let included = self.files.iter().position(|file| file == include);
+                if included.map_or(false, |included| self.once.contains(&included)) {
+                    self.body.push('\n');
+                    continue;
+                }
+                let source =
+                    self.resolver
+                        .resolve(include)
+                        .map_err(|error| PreprocessError::Resolve {
+                            path: include.to_string(),
+                            file: path.to_string(),
+                            line: line_number,
+                            error,
+                        })?;
+                self.expand(include, &source)?;
+                self.body
+                    .push_str(&format!("#line {} {}\n", line_number + 1, index));
+            } else {
+                self.body.push_str(line);
+                self.body.push('\n');
+            }
+        }
+
+        self.stack.pop();
+        Ok(())
+    }
+}
+
+/// Returns the text after the `#` of a preprocessor directive.
+fn directive(line: &str) -> Option<&str> {
+    line.trim_start()
+        .strip_prefix('#')
+        .map(|directive| directive.trim_start())
+}
+
+fn keyword<'a>(directive: &'a str, keyword: &str) -> Option<&'a str> {
+    if !directive.starts_with(keyword) {
+        return None;
+    }
+    let rest = &directive[keyword.len()..];
+    match rest.chars().next() {
+        None => Some(rest),
+        Some(c) if c.is_whitespace() || c == '"' => Some(rest),
+        Some(_) => None,
+    }
+}
+
+fn quoted(rest: &str) -> Option<&str> {
+    let rest = rest.trim();
+    if rest.len() >= 2 && rest.starts_with('"') && rest.ends_with('"') {
+        Some(&rest[1..rest.len() - 1])
+    } else {
+        None
+    }
+}
+
+impl PreprocessedShader {
+    /// Parses an info log produced by compiling this shader and replaces the
+    /// source string numbers with file names. Messages without a location are
+    /// skipped.
+    pub fn map_info_log(&self, log: &str) -> Vec<SourceDiagnostic> {
+        parse_shader_diagnostics(log)
+            .into_iter()
+            .filter_map(|diagnostic| {
+                let source_index = diagnostic.source_index?;
+                Some(SourceDiagnostic {
+                    file: self
+                        .files
+                        .get(source_index as usize)
+                        .cloned()
+                        .unwrap_or_else(|| source_index.to_string()),
+                    line: diagnostic.line?,
+                    severity: diagnostic.severity,
+                    message: diagnostic.message,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(files: &[(&str, &str)]) -> HashMap<String, String> {
+        files
+            .iter()
+            .map(|&(path, source)| (path.to_string(), source.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn expands_includes_with_line_directives() {
+        let mut resolver = files(&[
+            (
+                "main.frag",
+                "#version 330\n#include \"common.glsl\"\nvoid main() {}\n",
+            ),
+            ("common.glsl", "#pragma once\nfloat x;\n"),
+        ]);
+        let shader = ShaderPreprocessor::new()
+            .define("QUALITY", "2")
+            .define("DEBUG", "")
+            .process(&mut resolver, "main.frag")
+            .unwrap();
+        assert_eq!(
+            "#version 330\n\
+             #define QUALITY 2\n\
+             #define DEBUG\n\
+             #line 1 0\n\
+             \n\
+             #line 1 1\n\
+             \n\
+             float x;\n\
+             #line 3 0\n\
+             void main() {}\n",
+            shader.source
+        );
+        assert_eq!(vec!["main.frag", "common.glsl"], shader.files);
+    }
+
+    #[test]
+    fn injected_version_replaces_directives() {
+        let mut resolver = files(&[("a", "#version 330\nx\n")]);
+        let shader = ShaderPreprocessor::new()
+            .version("450 core")
+            .process(&mut resolver, "a")
+            .unwrap();
+        assert_eq!("#version 450 core\n#line 1 0\n\nx\n", shader.source);
+    }
+
+    #[test]
+    fn pragma_once_and_recursion() {
+        let mut resolver = files(&[
+            ("a", "#include \"b\"\n#include \"b\"\n"),
+            ("b", "#pragma once\nb\n"),
+        ]);
+        let shader = ShaderPreprocessor::new()
+            .process(&mut resolver, "a")
+            .unwrap();
+        assert_eq!(1, shader.source.matches("\nb\n").count());
+
+        let mut resolver = files(&[("a", "#include \"b\"\n"), ("b", "#include \"a\"\n")]);
+        match ShaderPreprocessor::new().process(&mut resolver, "a") {
+            Err(PreprocessError::Recursive { path, file, line }) => {
+                assert_eq!(("a", "b", 1), (path.as_str(), file.as_str(), line));
+            }
+            other => panic!("{:?}", other),
+        }
+
+        let mut resolver = files(&[("a", "\n#include <b>\n")]);
+        match ShaderPreprocessor::new().process(&mut resolver, "a") {
+            Err(PreprocessError::MalformedInclude { line: 2, .. }) => {}
+            other => panic!("{:?}", other),
+        }
+
+        let mut resolver = files(&[("a", "#include \"missing\"\n")]);
+        match ShaderPreprocessor::new().process(&mut resolver, "a") {
+            Err(PreprocessError::Resolve { path, .. }) => assert_eq!("missing", path),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn maps_driver_logs_to_files() {
+        let shader = PreprocessedShader {
+            source: String::new(),
+            files: vec!["main.frag".to_string(), "common.glsl".to_string()],
+        };
+        let log = "0(12) : error C0000: syntax error, unexpected '}'\n\
+                   1:3(10): error: `x' undeclared\n\
+                   ERROR: 1:7: 'y' : undeclared identifier\n\
+                   WARNING: 0:2: extension not supported\n\
+                   ERROR: 2 compilation errors.  No code generated.\n";
+        assert_eq!(
+            vec![
+                SourceDiagnostic {
+                    file: "main.frag".to_string(),
+                    line: 12,
+                    severity: DiagnosticSeverity::Error,
+                    message: "C0000: syntax error, unexpected '}'".to_string(),
+                },
+                SourceDiagnostic {
+                    file: "common.glsl".to_string(),
+                    line: 3,
+                    severity: DiagnosticSeverity::Error,
+                    message: "`x' undeclared".to_string(),
+                },
+                SourceDiagnostic {
+                    file: "common.glsl".to_string(),
+                    line: 7,
+                    severity: DiagnosticSeverity::Error,
+                    message: "'y' : undeclared identifier".to_string(),
+                },
+                SourceDiagnostic {
+                    file: "main.frag".to_string(),
+                    line: 2,
+                    severity: DiagnosticSeverity::Warning,
+                    message: "extension not supported".to_string(),
+                },
+            ],
+            shader.map_info_log(log)
+        );
+    }
+}
@@ -92,6 +92,13 @@ impl_enums! {
         Linked = TRUE,
     }
 
+    struct ReceivedInvalidValidateStatus(i32);
+    /// The validation status of a program pipeline.
+    ValidateStatus {
+        Invalid = FALSE,
+        Valid = TRUE,
+    }
+
     // struct ReceivedInvalidGetShaderivParam(u32);
     // /// Allowed values for the pname arguments of `glGetShaderiv`.
     // GetShaderivParam {
@@ -275,6 +282,7 @@ impl_enums! {
         DispatchIndirectBuffer = DISPATCH_INDIRECT_BUFFER,
         DrawIndirectBuffer = DRAW_INDIRECT_BUFFER,
         ElementArrayBuffer = ELEMENT_ARRAY_BUFFER,
+        ParameterBuffer = PARAMETER_BUFFER_ARB,
         PixelPackBuffer = PIXEL_PACK_BUFFER,
         PixelUnpackBuffer = PIXEL_UNPACK_BUFFER,
         QueryBuffer = QUERY_BUFFER,
@@ -313,6 +321,20 @@ impl_enums! {
         Patches = PATCHES,
     }
 
+    struct ReceivedInvalidTransformFeedbackPrimitiveMode(u32);
+    /// Allowed values for the primitiveMode argument of `glBeginTransformFeedback`.
+    TransformFeedbackPrimitiveMode {
+        Points = POINTS,
+        Lines = LINES,
+        Triangles = TRIANGLES,
+    }
+
+    struct ReceivedInvalidTransformFeedbackBufferMode(u32);
+    TransformFeedbackBufferMode {
+        InterleavedAttribs = INTERLEAVED_ATTRIBS,
+        SeparateAttribs = SEPARATE_ATTRIBS,
+    }
+
     struct ReceivedInvalidInternalFormat(i32);
     InternalFormat {
         DepthComponent = DEPTH_COMPONENT,
@@ -342,6 +364,7 @@ impl_enums! {
         Rgb5A1 = RGB5_A1,
         Rgba8 = RGBA8,
         Rgba8Snorm = RGBA8_SNORM,
+        Rgba16Snorm = RGBA16_SNORM,
         Rgb10A2 = RGB10_A2,
         Rgb10A2ui = RGB10_A2UI,
         Rgba12 = RGBA12,
@@ -418,6 +441,57 @@ impl_enums! {
         COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT = COMPRESSED_SRGB_ALPHA_S3TC_DXT5_EXT,
     }
 
+    struct ReceivedInvalidImageAccess(u32);
+    ImageAccess {
+        ReadOnly = READ_ONLY,
+        WriteOnly = WRITE_ONLY,
+        ReadWrite = READ_WRITE,
+    }
+
+    struct ReceivedInvalidImageFormat(u32);
+    /// The internal formats that can be used for image load/store.
+    ImageFormat {
+        Rgba32f = RGBA32F,
+        Rgba16f = RGBA16F,
+        Rg32f = RG32F,
+        Rg16f = RG16F,
+        R11fG11fB10f = R11F_G11F_B10F,
+        R32f = R32F,
+        R16f = R16F,
+        Rgba32ui = RGBA32UI,
+        Rgba16ui = RGBA16UI,
+        Rgb10A2ui = RGB10_A2UI,
+        Rgba8ui = RGBA8UI,
+        Rg32ui = RG32UI,
+        Rg16ui = RG16UI,
+        Rg8ui = RG8UI,
+        R32ui = R32UI,
+        R16ui = R16UI,
+        R8ui = R8UI,
+        Rgba32i = RGBA32I,
+        Rgba16i = RGBA16I,
+        Rgba8i = RGBA8I,
+        Rg32i = RG32I,
+        Rg16i = RG16I,
+        Rg8i = RG8I,
+        R32i = R32I,
+        R16i = R16I,
+        R8i = R8I,
+        Rgba16 = RGBA16,
+        Rgb10A2 = RGB10_A2,
+        Rgba8 = RGBA8,
+        Rg16 = RG16,
+        Rg8 = RG8,
+        R16 = R16,
+        R8 = R8,
+        Rgba16Snorm = RGBA16_SNORM,
+        Rgba8Snorm = RGBA8_SNORM,
+        Rg16Snorm = RG16_SNORM,
+        Rg8Snorm = RG8_SNORM,
+        R16Snorm = R16_SNORM,
+        R8Snorm = R8_SNORM,
+    }
+
     struct ReceivedInvalidFormat(i32);
     Format {
         Bgr = BGR,
@@ -507,6 +581,14 @@ impl_enums! {
         ProgramPointSize = PROGRAM_POINT_SIZE,
     }
 
+    struct ReceivedInvalidIndexedCapability(u32);
+    /// Capabilities that can be toggled per draw buffer or viewport with
+    /// `glEnablei` and `glDisablei`.
+    IndexedCapability {
+        Blend = BLEND,
+        ScissorTest = SCISSOR_TEST,
+    }
+
     struct ReceivedInvalidPolygonModeFace(u32);
     PolygonModeFace {
         FrontAndBack = FRONT_AND_BACK,
@@ -556,6 +638,57 @@ impl_enums! {
         Always = ALWAYS,
     }
 
+    struct ReceivedInvalidStencilFunc(u32);
+    StencilFunc {
+        Never = NEVER,
+        LT = LESS,
+        EQ = EQUAL,
+        LE = LEQUAL,
+        GT = GREATER,
+        NE = NOTEQUAL,
+        GE = GEQUAL,
+        Always = ALWAYS,
+    }
+
+    struct ReceivedInvalidStencilOp(u32);
+    StencilOp {
+        Keep = KEEP,
+        Zero = ZERO,
+        Replace = REPLACE,
+        Incr = INCR,
+        IncrWrap = INCR_WRAP,
+        Decr = DECR,
+        DecrWrap = DECR_WRAP,
+        Invert = INVERT,
+    }
+
+    struct ReceivedInvalidStencilFace(u32);
+    StencilFace {
+        Front = FRONT,
+        Back = BACK,
+        FrontAndBack = FRONT_AND_BACK,
+    }
+
+    struct ReceivedInvalidLogicOp(u32);
+    LogicOp {
+        Clear = CLEAR,
+        Set = SET,
+        Copy = COPY,
+        CopyInverted = COPY_INVERTED,
+        Noop = NOOP,
+        Invert = INVERT,
+        And = AND,
+        Nand = NAND,
+        Or = OR,
+        Nor = NOR,
+        Xor = XOR,
+        Equiv = EQUIV,
+        AndReverse = AND_REVERSE,
+        AndInverted = AND_INVERTED,
+        OrReverse = OR_REVERSE,
+        OrInverted = OR_INVERTED,
+    }
+
     struct ReceivedInvalidClipControlOrigin(u32);
     ClipControlOrigin {
         LowerLeft = LOWER_LEFT,
@@ -589,6 +722,14 @@ impl_enums! {
         TimeElapsed = TIME_ELAPSED,
     }
 
+    struct ReceivedInvalidQueryObjectParam(u32);
+    /// Allowed values for the pname argument of `glGetQueryBufferObject*v`.
+    QueryObjectParam {
+        QueryResult = QUERY_RESULT,
+        QueryResultNoWait = QUERY_RESULT_NO_WAIT,
+        QueryResultAvailable = QUERY_RESULT_AVAILABLE,
+        QueryTarget = QUERY_TARGET,
+    }
 
     struct ReceivedInvalidBlitFilter(u32);
     BlitFilter {
@@ -619,6 +760,136 @@ impl_enums! {
         OneMinusSrc1Alpha = ONE_MINUS_SRC1_ALPHA,
     }
 
+    struct ReceivedInvalidSyncWaitResult(u32);
+    /// Return values of `glClientWaitSync`.
+    SyncWaitResult {
+        AlreadySignaled = ALREADY_SIGNALED,
+        ConditionSatisfied = CONDITION_SATISFIED,
+        TimeoutExpired = TIMEOUT_EXPIRED,
+        WaitFailed = WAIT_FAILED,
+    }
+
+    struct ReceivedInvalidSyncStatus(i32);
+    SyncStatus {
+        Unsignaled = UNSIGNALED,
+        Signaled = SIGNALED,
+    }
+
+    struct ReceivedInvalidSyncCondition(i32);
+    SyncCondition {
+        SyncGpuCommandsComplete = SYNC_GPU_COMMANDS_COMPLETE,
+    }
+
+    struct ReceivedInvalidSyncObjectType(i32);
+    SyncObjectType {
+        SyncFence = SYNC_FENCE,
+    }
+
+    struct ReceivedInvalidDebugSource(u32);
+    /// Allowed values for the source argument of `glPushDebugGroup`.
+    DebugSource {
+        Application = DEBUG_SOURCE_APPLICATION,
+        ThirdParty = DEBUG_SOURCE_THIRD_PARTY,
+    }
+
+    struct ReceivedInvalidProgramInterface(u32);
+    ProgramInterface {
+        Uniform = UNIFORM,
+        UniformBlock = UNIFORM_BLOCK,
+        AtomicCounterBuffer = ATOMIC_COUNTER_BUFFER,
+        ProgramInput = PROGRAM_INPUT,
+        ProgramOutput = PROGRAM_OUTPUT,
+        TransformFeedbackVarying = TRANSFORM_FEEDBACK_VARYING,
+        TransformFeedbackBuffer = TRANSFORM_FEEDBACK_BUFFER,
+        BufferVariable = BUFFER_VARIABLE,
+        ShaderStorageBlock = SHADER_STORAGE_BLOCK,
+    }
+
+    struct ReceivedInvalidProgramResourceProperty(u32);
+    /// Allowed values for the props argument of `glGetProgramResourceiv`.
+    ProgramResourceProperty {
+        NameLength = NAME_LENGTH,
+        Type = TYPE,
+        ArraySize = ARRAY_SIZE,
+        Offset = OFFSET,
+        BlockIndex = BLOCK_INDEX,
+        ArrayStride = ARRAY_STRIDE,
+        MatrixStride = MATRIX_STRIDE,
+        IsRowMajor = IS_ROW_MAJOR,
+        AtomicCounterBufferIndex = ATOMIC_COUNTER_BUFFER_INDEX,
+        BufferBinding = BUFFER_BINDING,
+        BufferDataSize = BUFFER_DATA_SIZE,
+        NumActiveVariables = NUM_ACTIVE_VARIABLES,
+        ActiveVariables = ACTIVE_VARIABLES,
+        ReferencedByVertexShader = REFERENCED_BY_VERTEX_SHADER,
+        ReferencedByTessControlShader = REFERENCED_BY_TESS_CONTROL_SHADER,
+        ReferencedByTessEvaluationShader = REFERENCED_BY_TESS_EVALUATION_SHADER,
+        ReferencedByGeometryShader = REFERENCED_BY_GEOMETRY_SHADER,
+        ReferencedByFragmentShader = REFERENCED_BY_FRAGMENT_SHADER,
+        ReferencedByComputeShader = REFERENCED_BY_COMPUTE_SHADER,
+        TopLevelArraySize = TOP_LEVEL_ARRAY_SIZE,
+        TopLevelArrayStride = TOP_LEVEL_ARRAY_STRIDE,
+        Location = LOCATION,
+        LocationIndex = LOCATION_INDEX,
+        LocationComponent = LOCATION_COMPONENT,
+        IsPerPatch = IS_PER_PATCH,
+        TransformFeedbackBufferIndex = TRANSFORM_FEEDBACK_BUFFER_INDEX,
+        TransformFeedbackBufferStride = TRANSFORM_FEEDBACK_BUFFER_STRIDE,
+    }
+
+    struct ReceivedInvalidGlslType(u32);
+    /// The non-opaque GLSL types as returned for the `TYPE` program resource
+    /// property.
+    GlslType {
+        Float = FLOAT,
+        FloatVec2 = FLOAT_VEC2,
+        FloatVec3 = FLOAT_VEC3,
+        FloatVec4 = FLOAT_VEC4,
+        Double = DOUBLE,
+        DoubleVec2 = DOUBLE_VEC2,
+        DoubleVec3 = DOUBLE_VEC3,
+        DoubleVec4 = DOUBLE_VEC4,
+        Int = INT,
+        IntVec2 = INT_VEC2,
+        IntVec3 = INT_VEC3,
+        IntVec4 = INT_VEC4,
+        UnsignedInt = UNSIGNED_INT,
+        UnsignedIntVec2 = UNSIGNED_INT_VEC2,
+        UnsignedIntVec3 = UNSIGNED_INT_VEC3,
+        UnsignedIntVec4 = UNSIGNED_INT_VEC4,
+        Bool = BOOL,
+        BoolVec2 = BOOL_VEC2,
+        BoolVec3 = BOOL_VEC3,
+        BoolVec4 = BOOL_VEC4,
+        FloatMat2 = FLOAT_MAT2,
+        FloatMat2x3 = FLOAT_MAT2x3,
+        FloatMat2x4 = FLOAT_MAT2x4,
+        FloatMat3x2 = FLOAT_MAT3x2,
+        FloatMat3 = FLOAT_MAT3,
+        FloatMat3x4 = FLOAT_MAT3x4,
+        FloatMat4x2 = FLOAT_MAT4x2,
+        FloatMat4x3 = FLOAT_MAT4x3,
+        FloatMat4 = FLOAT_MAT4,
+        DoubleMat2 = DOUBLE_MAT2,
+        DoubleMat2x3 = DOUBLE_MAT2x3,
+        DoubleMat2x4 = DOUBLE_MAT2x4,
+        DoubleMat3x2 = DOUBLE_MAT3x2,
+        DoubleMat3 = DOUBLE_MAT3,
+        DoubleMat3x4 = DOUBLE_MAT3x4,
+        DoubleMat4x2 = DOUBLE_MAT4x2,
+        DoubleMat4x3 = DOUBLE_MAT4x3,
+        DoubleMat4 = DOUBLE_MAT4,
+    }
+
+    struct ReceivedInvalidBlendEquation(u32);
+    BlendEquation {
+        FuncAdd = FUNC_ADD,
+        FuncSubtract = FUNC_SUBTRACT,
+        FuncReverseSubtract = FUNC_REVERSE_SUBTRACT,
+        Min = MIN,
+        Max = MAX,
+    }
+
     struct ReceivedInvalidFramebufferBuffer(u32);
     FramebufferBuffer {
         Color = COLOR,
@@ -627,6 +898,98 @@ impl_enums! {
     }
 }
 
+/// Returned when converting an `InternalFormat` that can not be used for image
+/// load/store into an `ImageFormat`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct IncompatibleImageFormat(pub InternalFormat);
+
+impl std::fmt::Display for IncompatibleImageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "The internal format {:?} can not be used for image load/store.",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for IncompatibleImageFormat {}
+
+impl TryFrom<InternalFormat> for ImageFormat {
+    type Error = IncompatibleImageFormat;
+
+    #[inline]
+    fn try_from(format: InternalFormat) -> Result<Self, Self::Error> {
+        ImageFormat::try_from(format as u32).map_err(|_| IncompatibleImageFormat(format))
+    }
+}
+
+impl From<ImageFormat> for InternalFormat {
+    #[inline]
+    fn from(format: ImageFormat) -> Self {
+        // Every image format is also an internal format.
+        InternalFormat::try_from(format as i32).unwrap()
+    }
+}
+
+/// The scalar type of a `GlslType`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum GlslScalarType {
+    Float,
+    Double,
+    Int,
+    UnsignedInt,
+    Bool,
+}
+
+impl GlslType {
+    #[inline]
+    pub fn scalar_type(self) -> GlslScalarType {
+        use GlslType::*;
+        match self {
+            Float | FloatVec2 | FloatVec3 | FloatVec4 | FloatMat2 | FloatMat2x3 | FloatMat2x4
+            | FloatMat3x2 | FloatMat3 | FloatMat3x4 | FloatMat4x2 | FloatMat4x3 | FloatMat4 => {
+                GlslScalarType::Float
+            }
+            Double | DoubleVec2 | DoubleVec3 | DoubleVec4 | DoubleMat2 | DoubleMat2x3
+            | DoubleMat2x4 | DoubleMat3x2 | DoubleMat3 | DoubleMat3x4 | DoubleMat4x2
+            | DoubleMat4x3 | DoubleMat4 => GlslScalarType::Double,
+            Int | IntVec2 | IntVec3 | IntVec4 => GlslScalarType::Int,
+            UnsignedInt | UnsignedIntVec2 | UnsignedIntVec3 | UnsignedIntVec4 => {
+                GlslScalarType::UnsignedInt
+            }
+            Bool | BoolVec2 | BoolVec3 | BoolVec4 => GlslScalarType::Bool,
+        }
+    }
+
+    /// The number of columns of a matrix, 1 for scalars and vectors.
+    #[inline]
+    pub fn columns(self) -> u32 {
+        use GlslType::*;
+        match self {
+            FloatMat2 | FloatMat2x3 | FloatMat2x4 | DoubleMat2 | DoubleMat2x3 | DoubleMat2x4 => 2,
+            FloatMat3x2 | FloatMat3 | FloatMat3x4 | DoubleMat3x2 | DoubleMat3 | DoubleMat3x4 => 3,
+            FloatMat4x2 | FloatMat4x3 | FloatMat4 | DoubleMat4x2 | DoubleMat4x3 | DoubleMat4 => 4,
+            _ => 1,
+        }
+    }
+
+    /// The number of components of a vector or of a single matrix column.
+    #[inline]
+    pub fn rows(self) -> u32 {
+        use GlslType::*;
+        match self {
+            Float | Double | Int | UnsignedInt | Bool => 1,
+            FloatVec2 | DoubleVec2 | IntVec2 | UnsignedIntVec2 | BoolVec2 | FloatMat2
+            | FloatMat3x2 | FloatMat4x2 | DoubleMat2 | DoubleMat3x2 | DoubleMat4x2 => 2,
+            FloatVec3 | DoubleVec3 | IntVec3 | UnsignedIntVec3 | BoolVec3 | FloatMat2x3
+            | FloatMat3 | FloatMat4x3 | DoubleMat2x3 | DoubleMat3 | DoubleMat4x3 => 3,
+            FloatVec4 | DoubleVec4 | IntVec4 | UnsignedIntVec4 | BoolVec4 | FloatMat2x4
+            | FloatMat3x4 | FloatMat4 | DoubleMat2x4 | DoubleMat3x4 | DoubleMat4 => 4,
+        }
+    }
+}
+
 macro_rules! impl_struct_from_symbol {
     ($Struct:ident { $($Variant:ident = $Symbol:ident,)* }) => {
         $(
@@ -703,7 +1066,43 @@ impl_struct_from_symbol! (TextureUnit {
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 #[repr(transparent)]
-pub struct MaxColorAttachments(u32);
+pub struct MaxViewports(pub(crate) u32);
+
+impl MaxViewports {
+    #[inline]
+    pub(crate) fn to_u32(self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
+pub struct ViewportIndex(u32);
+
+impl ViewportIndex {
+    #[inline]
+    pub fn new(index: u32, max: MaxViewports) -> Option<Self> {
+        if index < max.to_u32() {
+            Some(ViewportIndex(index))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub const unsafe fn new_unchecked(index: u32) -> Self {
+        ViewportIndex(index)
+    }
+
+    #[inline]
+    pub fn to_u32(self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[repr(transparent)]
+pub struct MaxColorAttachments(pub(crate) u32);
 
 impl MaxColorAttachments {
     #[deprecated]
@@ -832,6 +1231,24 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    pub struct ShaderStageFlag : u32 {
+        const VERTEX = crate::gl::VERTEX_SHADER_BIT;
+        const TESS_CONTROL = crate::gl::TESS_CONTROL_SHADER_BIT;
+        const TESS_EVALUATION = crate::gl::TESS_EVALUATION_SHADER_BIT;
+        const GEOMETRY = crate::gl::GEOMETRY_SHADER_BIT;
+        const FRAGMENT = crate::gl::FRAGMENT_SHADER_BIT;
+        const COMPUTE = crate::gl::COMPUTE_SHADER_BIT;
+        const ALL = crate::gl::ALL_SHADER_BITS;
+    }
+}
+
+bitflags::bitflags! {
+    pub struct SyncFlags : u32 {
+        const SYNC_FLUSH_COMMANDS_BIT = crate::gl::SYNC_FLUSH_COMMANDS_BIT;
+    }
+}
+
 bitflags::bitflags! {
     pub struct MemoryBarrierFlag : u32 {
         const VERTEX_ATTRIB_ARRAY = crate::gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT;
@@ -870,3 +1287,31 @@ impl PixelAlignment {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_formats_round_trip_through_internal_formats() {
+        // Every image format is a GL enum below 0x10000.
+        let image_formats: Vec<ImageFormat> = (0..0x1_0000u32)
+            .filter_map(|value| ImageFormat::try_from(value).ok())
+            .collect();
+        assert_eq!(39, image_formats.len());
+        for format in image_formats {
+            assert_eq!(
+                Ok(format),
+                ImageFormat::try_from(InternalFormat::from(format))
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_incompatible_image_formats() {
+        assert_eq!(
+            Err(IncompatibleImageFormat(InternalFormat::Rgb8)),
+            ImageFormat::try_from(InternalFormat::Rgb8)
+        );
+    }
+}
@@ -0,0 +1,418 @@
+use crate::*;
+use std::fmt;
+
+/// An active input of the first stage of a program.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProgramInput {
+    pub name: String,
+    pub ty: GlslType,
+    pub array_size: u32,
+    pub location: AttributeLocation,
+}
+
+impl ProgramInput {
+    /// Every location this input occupies. Matrices occupy one location per
+    /// column, or two when the columns are `dvec3` or `dvec4`.
+    pub fn locations(&self) -> impl Iterator<Item = AttributeLocation> {
+        let first = self.location.to_i32();
+        let count = (self.array_size * self.ty.columns() * self.locations_per_column()) as i32;
+        (first..first + count)
+            .map(|location| unsafe { AttributeLocation::from_i32_unchecked(location) })
+    }
+
+    /// The first location of every column. Only these locations have their
+    /// own attribute format.
+    fn column_locations(&self) -> impl Iterator<Item = AttributeLocation> {
+        self.locations()
+            .step_by(self.locations_per_column() as usize)
+    }
+
+    fn locations_per_column(&self) -> u32 {
+        if self.ty.scalar_type() == GlslScalarType::Double && self.ty.rows() > 2 {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+/// A difference between how a vertex array is configured and what a program
+/// expects.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum VertexInputMismatch {
+    /// The input will read the current generic vertex attribute.
+    Disabled {
+        input: String,
+        location: AttributeLocation,
+    },
+    /// The attribute format can not be represented as an `AttributeFormat`.
+    UnknownFormat {
+        input: String,
+        location: AttributeLocation,
+    },
+    /// Feeding float data to an integer input or the other way around
+    /// produces undefined values.
+    Class {
+        input: String,
+        location: AttributeLocation,
+        format: AttributeFormat,
+        ty: GlslType,
+    },
+    /// The format supplies more components than the input reads. The extra
+    /// components are ignored. This is allowed but usually not intended.
+    /// Missing components are filled in from (0, 0, 0, 1) and are not
+    /// reported.
+    ComponentCount {
+        input: String,
+        location: AttributeLocation,
+        format: AttributeFormat,
+        ty: GlslType,
+    },
+}
+
+impl fmt::Display for VertexInputMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VertexInputMismatch::Disabled { input, location } => write!(
+                f,
+                "Input {} at location {} has no enabled attribute.",
+                input,
+                location.to_i32()
+            ),
+            VertexInputMismatch::UnknownFormat { input, location } => write!(
+                f,
+                "Input {} at location {} is fed by an unsupported attribute format.",
+                input,
+                location.to_i32()
+            ),
+            VertexInputMismatch::Class {
+                input,
+                location,
+                format,
+                ty,
+            } => write!(
+                f,
+                "Input {} at location {} of type {:?} is fed by incompatible attribute format {:?}.",
+                input,
+                location.to_i32(),
+                ty,
+                format
+            ),
+            VertexInputMismatch::ComponentCount {
+                input,
+                location,
+                format,
+                ty,
+            } => write!(
+                f,
+                "Input {} at location {} of type {:?} is fed by attribute format {:?} with more components.",
+                input,
+                location.to_i32(),
+                ty,
+                format
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VertexInputMismatch {}
+
+/// Compares the state of a single attribute with the input reading it.
+/// `format` is only used when `enabled` is true.
+pub fn check_vertex_input(
+    input: &ProgramInput,
+    location: AttributeLocation,
+    enabled: bool,
+    format: Option<AttributeFormat>,
+) -> Option<VertexInputMismatch> {
+    let name = || input.name.clone();
+    if !enabled {
+        return Some(VertexInputMismatch::Disabled {
+            input: name(),
+            location,
+        });
+    }
+    let format = match format {
+        Some(format) => format,
+        None => {
+            return Some(VertexInputMismatch::UnknownFormat {
+                input: name(),
+                location,
+            })
+        }
+    };
+    let class_matches = matches!(
+        (input.ty.scalar_type(), format),
+        (GlslScalarType::Float, AttributeFormat::F(_))
+            | (GlslScalarType::Double, AttributeFormat::L(_))
+            | (
+                GlslScalarType::Int | GlslScalarType::UnsignedInt | GlslScalarType::Bool,
+                AttributeFormat::I(_)
+            )
+    );
+    if !class_matches {
+        return Some(VertexInputMismatch::Class {
+            input: name(),
+            location,
+            format,
+            ty: input.ty,
+        });
+    }
+    if format.component_count().to_u32() > input.ty.rows() {
+        return Some(VertexInputMismatch::ComponentCount {
+            input: name(),
+            location,
+            format,
+            ty: input.ty,
+        });
+    }
+    None
+}
+
+impl Gl {
+    /// The active inputs of the first stage of a program. Built-in inputs
+    /// like `gl_VertexID` have no location and are skipped.
+    pub unsafe fn get_program_inputs(&self, program_name: ProgramName) -> Vec<ProgramInput> {
+        let count = self.get_program_interfaceiv(
+            program_name,
+            ProgramInterface::ProgramInput,
+            ACTIVE_RESOURCES,
+        );
+        let mut inputs = Vec::with_capacity(count as usize);
+        for index in 0..count {
+            let mut values = [0; 3];
+            self.get_program_resourceiv(
+                program_name,
+                ProgramInterface::ProgramInput,
+                index,
+                &[
+                    ProgramResourceProperty::Type,
+                    ProgramResourceProperty::ArraySize,
+                    ProgramResourceProperty::Location,
+                ],
+                &mut values,
+            );
+            let [ty, array_size, location] = values;
+            let location = match AttributeLocation::from_i32(location) {
+                Some(location) => location,
+                None => continue,
+            };
+            inputs.push(ProgramInput {
+                name: self.get_program_resource_name(
+                    program_name,
+                    ProgramInterface::ProgramInput,
+                    index,
+                ),
+                ty: GlslType::try_from(ty as u32).unwrap(),
+                array_size: array_size as u32,
+                location,
+            });
+        }
+        inputs
+    }
+
+    /// Checks that every location read by the vertex inputs of
+    /// `program_name` is enabled in `vertex_array_name` with a compatible
+    /// format.
+    pub unsafe fn validate_vertex_array(
+        &self,
+        vertex_array_name: VertexArrayName,
+        program_name: ProgramName,
+    ) -> Result<(), Vec<VertexInputMismatch>> {
+        let mut mismatches = Vec::new();
+        for input in self.get_program_inputs(program_name) {
+            for location in input.column_locations() {
+                let enabled = self.get_vertex_array_indexediv(
+                    vertex_array_name,
+                    location,
+                    VERTEX_ATTRIB_ARRAY_ENABLED,
+                );
+                let format = if enabled {
+                    self.get_vertex_array_attrib_format(vertex_array_name, location)
+                } else {
+                    None
+                };
+                mismatches.extend(check_vertex_input(&input, location, enabled, format));
+            }
+        }
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(ty: GlslType, array_size: u32) -> ProgramInput {
+        ProgramInput {
+            name: String::from("a"),
+            ty,
+            array_size,
+            location: AttributeLocation::from_i32(2).unwrap(),
+        }
+    }
+
+    fn location(value: i32) -> AttributeLocation {
+        AttributeLocation::from_i32(value).unwrap()
+    }
+
+    #[test]
+    fn matrices_and_arrays_occupy_multiple_locations() {
+        let locations = |ty, n| {
+            input(ty, n)
+                .locations()
+                .map(|l| l.to_i32())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![2], locations(GlslType::FloatVec4, 1));
+        assert_eq!(vec![2, 3, 4], locations(GlslType::FloatMat3x2, 1));
+        assert_eq!(vec![2, 3, 4, 5], locations(GlslType::DoubleMat2, 2));
+        assert_eq!(vec![2, 3, 4, 5], locations(GlslType::DoubleVec4, 2));
+        assert_eq!(
+            (2..10).collect::<Vec<_>>(),
+            locations(GlslType::DoubleMat4, 1)
+        );
+        assert_eq!(
+            vec![2, 4, 6, 8],
+            input(GlslType::DoubleMat4, 1)
+                .column_locations()
+                .map(|l| l.to_i32())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn reports_class_mismatch() {
+        let format = AttributeFormatF::F32(ComponentCount::P4).into();
+        assert_eq!(
+            Some(VertexInputMismatch::Class {
+                input: String::from("a"),
+                location: location(2),
+                format,
+                ty: GlslType::IntVec4,
+            }),
+            check_vertex_input(
+                &input(GlslType::IntVec4, 1),
+                location(2),
+                true,
+                Some(format)
+            )
+        );
+        let format = AttributeFormatI::U8(ComponentCount::P4).into();
+        assert!(check_vertex_input(
+            &input(GlslType::FloatVec4, 1),
+            location(2),
+            true,
+            Some(format)
+        )
+        .is_some());
+        let format = AttributeFormatF::F32(ComponentCount::P2).into();
+        assert!(check_vertex_input(
+            &input(GlslType::DoubleVec2, 1),
+            location(2),
+            true,
+            Some(format)
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn accepts_matching_formats() {
+        let cases: &[(GlslType, AttributeFormat)] = &[
+            (
+                GlslType::FloatVec4,
+                AttributeFormatF::U8(ComponentCount::P4, true).into(),
+            ),
+            (
+                GlslType::FloatVec4,
+                AttributeFormatF::I_2_10_10_10_REV(true).into(),
+            ),
+            (
+                GlslType::FloatVec3,
+                AttributeFormatF::U_10F_11F_11F_REV.into(),
+            ),
+            (
+                GlslType::UnsignedIntVec2,
+                AttributeFormatI::U16(ComponentCount::P2).into(),
+            ),
+            (
+                GlslType::Int,
+                AttributeFormatI::I32(ComponentCount::P1).into(),
+            ),
+            (
+                GlslType::DoubleVec3,
+                AttributeFormatL::F64(ComponentCount::P3).into(),
+            ),
+            (
+                GlslType::FloatMat4x2,
+                AttributeFormatF::F32(ComponentCount::P2).into(),
+            ),
+            (
+                GlslType::FloatVec4,
+                AttributeFormatF::F32(ComponentCount::P3).into(),
+            ),
+            (
+                GlslType::DoubleVec4,
+                AttributeFormatL::F64(ComponentCount::P1).into(),
+            ),
+        ];
+        for &(ty, format) in cases {
+            assert_eq!(
+                None,
+                check_vertex_input(&input(ty, 1), location(2), true, Some(format))
+            );
+        }
+    }
+
+    #[test]
+    fn reports_component_count_disabled_and_unknown() {
+        let format = AttributeFormatF::F32(ComponentCount::P4).into();
+        assert!(matches!(
+            check_vertex_input(
+                &input(GlslType::FloatVec3, 1),
+                location(2),
+                true,
+                Some(format)
+            ),
+            Some(VertexInputMismatch::ComponentCount { .. })
+        ));
+        assert!(matches!(
+            check_vertex_input(&input(GlslType::FloatVec4, 1), location(2), false, None),
+            Some(VertexInputMismatch::Disabled { .. })
+        ));
+        assert!(matches!(
+            check_vertex_input(&input(GlslType::FloatVec4, 1), location(2), true, None),
+            Some(VertexInputMismatch::UnknownFormat { .. })
+        ));
+    }
+
+    #[test]
+    fn reconstructs_formats_from_state() {
+        assert_eq!(
+            Some(AttributeFormatF::U8(ComponentCount::P4, true).into()),
+            AttributeFormat::from_vertex_array_state(
+                gl::BGRA,
+                gl::UNSIGNED_BYTE,
+                true,
+                false,
+                false
+            )
+        );
+        assert_eq!(
+            Some(AttributeFormatI::I16(ComponentCount::P2).into()),
+            AttributeFormat::from_vertex_array_state(2, gl::SHORT, false, true, false)
+        );
+        assert_eq!(
+            Some(AttributeFormatL::F64(ComponentCount::P3).into()),
+            AttributeFormat::from_vertex_array_state(3, gl::DOUBLE, false, false, true)
+        );
+        assert_eq!(
+            None,
+            AttributeFormat::from_vertex_array_state(4, gl::DOUBLE, false, false, false)
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use crate::*;
+
+/// A field type that maps to a single vertex attribute. Integer types map to
+/// integer attributes. Use an explicit format to read them as normalized or
+/// converted floats.
+pub trait VertexAttributeType {
+    const FORMAT: AttributeFormat;
+}
+
+macro_rules! impl_vertex_attribute_types {
+    ($($Kind:ident($Format:ident::$Variant:ident) { $($ty:ty => $count:ident,)* })*) => {
+        $(
+            $(
+                impl VertexAttributeType for $ty {
+                    const FORMAT: AttributeFormat =
+                        AttributeFormat::$Kind($Format::$Variant(ComponentCount::$count));
+                }
+            )*
+        )*
+    };
+}
+
+impl_vertex_attribute_types! {
+    F(AttributeFormatF::F32) {
+        f32 => P1,
+        [f32; 1] => P1,
+        [f32; 2] => P2,
+        [f32; 3] => P3,
+        [f32; 4] => P4,
+    }
+    L(AttributeFormatL::F64) {
+        f64 => P1,
+        [f64; 1] => P1,
+        [f64; 2] => P2,
+        [f64; 3] => P3,
+        [f64; 4] => P4,
+    }
+    I(AttributeFormatI::I8) {
+        i8 => P1,
+        [i8; 1] => P1,
+        [i8; 2] => P2,
+        [i8; 3] => P3,
+        [i8; 4] => P4,
+    }
+    I(AttributeFormatI::U8) {
+        u8 => P1,
+        [u8; 1] => P1,
+        [u8; 2] => P2,
+        [u8; 3] => P3,
+        [u8; 4] => P4,
+    }
+    I(AttributeFormatI::I16) {
+        i16 => P1,
+        [i16; 1] => P1,
+        [i16; 2] => P2,
+        [i16; 3] => P3,
+        [i16; 4] => P4,
+    }
+    I(AttributeFormatI::U16) {
+        u16 => P1,
+        [u16; 1] => P1,
+        [u16; 2] => P2,
+        [u16; 3] => P3,
+        [u16; 4] => P4,
+    }
+    I(AttributeFormatI::I32) {
+        i32 => P1,
+        [i32; 1] => P1,
+        [i32; 2] => P2,
+        [i32; 3] => P3,
+        [i32; 4] => P4,
+    }
+    I(AttributeFormatI::U32) {
+        u32 => P1,
+        [u32; 1] => P1,
+        [u32; 2] => P2,
+        [u32; 3] => P3,
+        [u32; 4] => P4,
+    }
+}
+
+/// A single attribute of a `VertexLayout`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct VertexAttribute {
+    pub name: &'static str,
+    pub format: AttributeFormat,
+    /// Relative to the start of the vertex.
+    pub offset: u32,
+    /// The size of the field which must be able to hold the format.
+    pub byte_size: u32,
+}
+
+/// Describes how a vertex struct maps to attributes. Usually implemented
+/// through `#[derive(VertexLayout)]` which requires the `derive` feature.
+pub trait VertexLayout: Sized {
+    fn attributes() -> Vec<VertexAttribute>;
+
+    #[inline]
+    fn stride() -> u32 {
+        std::mem::size_of::<Self>() as u32
+    }
+}
+
+impl Gl {
+    /// Sets up the format of every attribute of `V`, binds them to
+    /// `vertex_array_buffer_binding_index` and enables them. The attributes
+    /// are assigned `attribute_locations` in order. The buffer itself still
+    /// needs to be attached with `vertex_array_vertex_buffer` using
+    /// `V::stride()`.
+    pub unsafe fn setup_vertex_array<V: VertexLayout>(
+        &self,
+        vertex_array_name: VertexArrayName,
+        vertex_array_buffer_binding_index: VertexArrayBufferBindingIndex,
+        attribute_locations: &[AttributeLocation],
+    ) {
+        let attributes = V::attributes();
+        assert_eq!(attributes.len(), attribute_locations.len());
+        for (attribute, &location) in attributes.iter().zip(attribute_locations.iter()) {
+            debug_assert!(
+                attribute.format.byte_size() <= attribute.byte_size,
+                "The format of attribute {} does not fit in its field.",
+                attribute.name
+            );
+            self.vertex_array_attrib_format(
+                vertex_array_name,
+                location,
+                attribute.format,
+                attribute.offset,
+            );
+            self.vertex_array_attrib_binding(
+                vertex_array_name,
+                location,
+                vertex_array_buffer_binding_index,
+            );
+            self.enable_vertex_array_attrib(vertex_array_name, location);
+        }
+    }
+}
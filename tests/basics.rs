@@ -39,27 +39,16 @@ impl Basics {
     }
 }
 
-unsafe fn get_shader_info_log(gl: &Gl, name: &ShaderName) -> String {
-    let buffer = {
-        let capacity = {
-            let mut capacity: i32 = std::mem::uninitialized();
-            gl.get_shaderiv(&name, INFO_LOG_LENGTH, &mut capacity);
-            assert!(capacity > 0);
-            capacity as usize
-        };
-
-        let mut buffer: Vec<u8> = Vec::with_capacity(capacity);
-        buffer.set_len(capacity);
-        let mut length: i32 = std::mem::uninitialized();
-        gl.get_shader_info_log(&name, &mut length, &mut buffer[..]);
-        assert!(length > 0);
-        let length = length as usize;
-        assert!(length <= capacity);
-        buffer.set_len(length);
-        buffer
-    };
-
-    String::from_utf8(buffer).expect("Shader info log contains invalid UTF-8.")
+unsafe fn compile(gl: &Gl, kind: ShaderKind, source: &str) -> ShaderName {
+    let name = gl.create_shader(kind);
+    gl.shader_source(name, &[source.as_bytes()]);
+    if let Err(diagnostics) = gl.compile_shader_checked(name) {
+        for diagnostic in diagnostics {
+            eprintln!("{}", diagnostic);
+        }
+        panic!("Failed to compile shader.");
+    }
+    name
 }
 
 #[test]
@@ -70,46 +59,14 @@ fn create_a_shader() {
         gl,
     } = Basics::new();
 
-    let vs = unsafe {
-        let mut name: ShaderName = gl.create_shader(VERTEX_SHADER).unwrap();
-        gl.shader_source(&mut name, &[BASIC_VERTEX_SHADER.as_bytes()]);
-        gl.compile_shader(&mut name);
-        let mut status: RawCompileStatus = std::mem::uninitialized();
-        gl.get_shaderiv(&name, COMPILE_STATUS, &mut status);
-        match status.into() {
-            CompileStatus::Uncompiled => {
-                eprintln!("{}", get_shader_info_log(&gl, &name));
-                panic!("Failed to compile shader.");
-            }
-            CompileStatus::Compiled => name,
-        }
-    };
-
-    let fs = unsafe {
-        let mut name: ShaderName = gl.create_shader(FRAGMENT_SHADER).unwrap();
-        gl.shader_source(&mut name, &[BASIC_FRAGMENT_SHADER.as_bytes()]);
-        gl.compile_shader(&mut name);
-        let mut status: RawCompileStatus = std::mem::uninitialized();
-        gl.get_shaderiv(&name, COMPILE_STATUS, &mut status);
-        match status.into() {
-            CompileStatus::Uncompiled => {
-                eprintln!("{}", get_shader_info_log(&gl, &name));
-                panic!("Failed to compile shader.");
-            }
-            CompileStatus::Compiled => name,
-        }
-    };
-
-    let p = unsafe {
-        let mut name = gl.create_program().unwrap();
-        gl.attach_shader(&mut name, &vs);
-        gl.attach_shader(&mut name, &fs);
-        gl.link_program(&mut name);
-        let mut status: RawLinkStatus = std::mem::uninitialized();
-        gl.get_programiv(&name, LINK_STATUS, &mut status);
-        assert_eq!(status, LinkStatus::Linked.into());
-        name
-    };
+    unsafe {
+        let vs = compile(&gl, ShaderKind::VertexShader, BASIC_VERTEX_SHADER);
+        let fs = compile(&gl, ShaderKind::FragmentShader, BASIC_FRAGMENT_SHADER);
+        let p = gl.create_program();
+        gl.attach_shader(p, vs);
+        gl.attach_shader(p, fs);
+        gl.link_program_checked(p).unwrap();
+    }
 }
 
 const BASIC_VERTEX_SHADER: &'static str = r##"